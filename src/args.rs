@@ -1,5 +1,12 @@
-use clap::{Parser, ValueEnum};
+use std::collections::HashSet;
 
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::collect::{CatalogEnforce, LintOptions};
+use crate::config::ConfigFile;
+use crate::git::Since;
+use crate::net::{DEFAULT_RETRIES, DEFAULT_TIMEOUT_SECS, NetworkOptions};
+use crate::registry::DEFAULT_REGISTRY;
 use crate::rules::Filter;
 
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -7,6 +14,65 @@ pub enum OutputFormat {
     #[default]
     Text,
     Json,
+    /// One row per issue, for spreadsheets
+    Csv,
+    Tsv,
+    /// JUnit XML, for CI test reporters
+    Junit,
+}
+
+/// How `--format junit` groups issues into test suites.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum JunitGroup {
+    /// One suite per package, one test case per rule
+    #[default]
+    Package,
+    /// One suite per rule, one test case per package
+    Rule,
+}
+
+/// Shape of the `issues` value in `--format json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum JsonShape {
+    /// One array of issues
+    #[default]
+    Flat,
+    /// An object of issue arrays keyed by package, like the text report
+    Grouped,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Check the workspace setup for common problems without linting
+    Doctor {
+        /// Path to the workspace root
+        #[arg(default_value = ".")]
+        path: String,
+    },
+    /// Write a starter pnpm-catalog-lint.toml to the workspace root
+    Init {
+        /// Path to the workspace root
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// List every catalog entry with the number of packages using it
+    CatalogReport {
+        /// Path to the workspace root
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Output format: text, json
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+
+        /// Write JSON output on a single line instead of indenting it
+        #[arg(long)]
+        compact: bool,
+    },
 }
 
 #[derive(Parser, Debug)]
@@ -16,8 +82,11 @@ pub enum OutputFormat {
     version
 )]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Path to the workspace root
-    #[arg(default_value = ".")]
+    #[arg(default_value = ".", env = "PNPM_CATALOG_LINT_ROOT")]
     pub path: String,
 
     /// Rules to exclude (can be specified multiple times)
@@ -28,12 +97,28 @@ pub struct Args {
     #[arg(long = "only-rule", conflicts_with = "exclude_rules")]
     pub only_rules: Vec<String>,
 
+    /// Shortcut for --exclude-rule unused-catalog-entry
+    #[arg(long)]
+    pub no_unused: bool,
+
+    /// Shortcut for --exclude-rule no-direct-version
+    #[arg(long)]
+    pub no_direct: bool,
+
+    /// Shortcut for --exclude-rule catalog-entry-exists
+    #[arg(long)]
+    pub no_missing: bool,
+
     /// Packages to exclude (can be specified multiple times)
     #[arg(long = "exclude-package", conflicts_with = "only_packages")]
     pub exclude_packages: Vec<String>,
 
     /// Run only on specified packages (can be specified multiple times)
-    #[arg(long = "only-package", conflicts_with = "exclude_packages")]
+    #[arg(
+        long = "only-package",
+        visible_alias = "package",
+        conflicts_with = "exclude_packages"
+    )]
     pub only_packages: Vec<String>,
 
     /// Dependencies to exclude (can be specified multiple times)
@@ -44,6 +129,71 @@ pub struct Args {
     #[arg(long = "only-dependency", conflicts_with = "exclude_dependencies")]
     pub only_dependencies: Vec<String>,
 
+    /// Dependencies allowed to use direct versions even when cataloged (can be specified multiple times)
+    #[arg(long = "allow-direct", value_name = "DEP")]
+    pub allow_direct: Vec<String>,
+
+    /// Catalogs whose unused entries aren't reported, `default` for the default catalog (can be specified multiple times)
+    #[arg(long = "ignore-unused-in", value_name = "CATALOG")]
+    pub ignore_unused_in: Vec<String>,
+
+    /// Lint only these catalogs, `default` for the default catalog; references to others pass (can be specified multiple times)
+    #[arg(long = "catalog", value_name = "NAME")]
+    pub only_catalogs: Vec<String>,
+
+    /// Require catalog: references for dependencies shared by at least N packages
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub catalog_threshold: Option<u64>,
+
+    /// Accept direct versions that equal the catalog's version string exactly
+    #[arg(long)]
+    pub allow_matching_direct: bool,
+
+    /// Only report issues in packages whose package.json changed within a window,
+    /// e.g. 7d, 2w, 12h or 2024-05-01
+    #[arg(long, value_name = "WHEN")]
+    pub since: Option<Since>,
+
+    /// File with package directory globs to skip, one per line (# starts a comment)
+    #[arg(long, value_name = "FILE")]
+    pub ignore_path: Option<String>,
+
+    /// Print each loaded package and the packages: glob that matched it to stderr
+    #[arg(long)]
+    pub verbose: bool,
+
+    /// Warn about packages: globs that match directories, none of which has a package.json
+    #[arg(long)]
+    pub warn_empty_matches: bool,
+
+    /// Lint the package.json inside an npm pack tarball against the workspace catalogs
+    #[arg(long, value_name = "FILE", conflicts_with = "fix")]
+    pub from_tarball: Option<String>,
+
+    /// Lint only the catalogs in this yaml file with the catalog-level rules, without loading packages
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["fix", "from_tarball", "since"])]
+    pub catalog_only: Option<String>,
+
+    /// Read catalogs and packages: globs from this yaml file instead of <PATH>/pnpm-workspace.yaml
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["fix", "patch_output", "catalog_only"])]
+    pub workspace_file: Option<String>,
+
+    /// Show file paths relative to this directory instead of the workspace root
+    #[arg(long, value_name = "DIR")]
+    pub relative_to: Option<String>,
+
+    /// Write the changes --fix would make to FILE as a unified diff, without touching the workspace
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["fix", "from_tarball", "catalog_only", "catalog_source"])]
+    pub patch_output: Option<String>,
+
+    /// Use a JSON or YAML { name: version } map as the default catalog instead of pnpm-workspace.yaml's
+    #[arg(long, value_name = "FILE", conflicts_with = "fix")]
+    pub catalog_source: Option<String>,
+
+    /// Which packages must use catalogs, based on their package.json "private" field
+    #[arg(long, value_enum, default_value_t)]
+    pub catalog_enforce: CatalogEnforce,
+
     /// Automatically fix issues (supports no-direct-version and unused-catalog-entry)
     #[arg(long)]
     pub fix: bool,
@@ -52,25 +202,167 @@ pub struct Args {
     #[arg(long)]
     pub fail_on_warnings: bool,
 
+    /// Report every warning as an error; policies with an explicit level keep it
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Fail when less than PERCENT of references to cataloged dependencies use catalog:
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    pub min_coverage: Option<f64>,
+
+    /// On a failing run, print which condition caused the non-zero exit code to stderr
+    #[arg(long)]
+    pub explain_exit: bool,
+
+    /// Check that catalog: peers overlap the package's own declaration of the same dependency
+    #[arg(long)]
+    pub peer_consistency: bool,
+
+    /// Check catalog ranges against the versions resolved in pnpm-lock.yaml
+    #[arg(long)]
+    pub with_lockfile: bool,
+
+    /// Check catalog versions against the npm registry (requires network access)
+    #[arg(long)]
+    pub verify_registry: bool,
+
+    /// Registry used by --verify-registry
+    #[arg(long, value_name = "URL", default_value = DEFAULT_REGISTRY)]
+    pub registry: String,
+
+    /// Compare catalog versions with a reference catalog fetched from URL (JSON or YAML)
+    #[arg(long, value_name = "URL")]
+    pub reference_catalog_url: Option<String>,
+
+    /// Seconds before a registry or reference catalog request times out
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_TIMEOUT_SECS, value_parser = clap::value_parser!(u64).range(1..))]
+    pub registry_timeout: u64,
+
+    /// Retries after a registry or reference catalog request fails with a timeout, connection error, 5xx or 429
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_RETRIES)]
+    pub registry_retries: u32,
+
+    /// Print the number of issues per rule after the summary line
+    #[arg(long)]
+    pub rule_summary: bool,
+
+    /// Print a per-catalog usage breakdown
+    #[arg(long)]
+    pub stats: bool,
+
+    /// Number of threads used to load packages (0 = one per CPU)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub threads: usize,
+
     /// Output format
     #[arg(long, value_enum, default_value_t)]
     pub format: OutputFormat,
 
+    /// List issues from the most recently committed package.json first (runs git per package)
+    #[arg(long, conflicts_with = "stream")]
+    pub sort_by_blame: bool,
+
+    /// Test suite grouping for --format junit
+    #[arg(long, value_enum, default_value_t)]
+    pub junit_group: JunitGroup,
+
+    /// Shape of the issues in --format json: one array, or grouped by package
+    #[arg(long, value_enum, default_value_t)]
+    pub json_shape: JsonShape,
+
+    /// Print the summary line and --fix messages to stderr, leaving stdout to the report
+    #[arg(long)]
+    pub summary_stderr: bool,
+
+    /// Write JSON output on a single line instead of indenting it (ignored by other formats)
+    #[arg(long)]
+    pub compact: bool,
+
+    /// List at most N issues in the text report; the summary still counts all of them
+    #[arg(long, value_name = "N", conflicts_with = "stream")]
+    pub max_issues: Option<usize>,
+
+    /// Print each package's issues as soon as it is linted (text format only)
+    #[arg(long, conflicts_with_all = ["fix", "output", "from_tarball"])]
+    pub stream: bool,
+
+    /// Write the report to a file instead of stdout; stdout then only shows a summary
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<String>,
+
     /// Disable colored output
     #[arg(long)]
     pub no_color: bool,
 
+    /// Print the effective configuration and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Print the root, packages, catalogs and options a run would use, then exit without linting
+    #[arg(long, conflicts_with_all = ["fix", "stream", "output"])]
+    pub dry_run: bool,
+
     /// Suppress all output (exit code only)
     #[arg(long, short)]
     pub quiet: bool,
 }
 
 impl Args {
+    pub fn network_options(&self) -> NetworkOptions {
+        NetworkOptions {
+            timeout: std::time::Duration::from_secs(self.registry_timeout),
+            retries: self.registry_retries,
+            ..Default::default()
+        }
+    }
+
+    pub fn lint_options(&self, config: &ConfigFile) -> LintOptions {
+        LintOptions {
+            rule_filter: self.rule_filter(),
+            package_filter: self.package_filter(),
+            dependency_filter: self.dependency_filter(),
+            catalog_enforce: self.catalog_enforce,
+            allow_direct: self.allow_direct.clone(),
+            allow_matching_direct: self.allow_matching_direct,
+            peer_consistency: self.peer_consistency,
+            policies: config.policies.clone(),
+            reserved_entries: config.reserved_entries(),
+            ignore_unused_in: self.ignore_unused_in.clone(),
+            only_catalogs: self.only_catalogs.clone(),
+            permissive_specs: config.permissive_specs(),
+            allow_prerelease: config.allow_prerelease.clone(),
+            message_templates: config.message_templates(),
+            strict: self.strict,
+            // Needs the loaded packages; filled in for --since once they are
+            unchanged_packages: HashSet::new(),
+            catalog_threshold: self.catalog_threshold.map(|n| n as usize),
+            // Needs the workspace root; set once it is found
+            relative_to: None,
+        }
+    }
+
     pub fn rule_filter(&self) -> Filter {
+        let shortcuts: Vec<&str> = [
+            (self.no_unused, "unused-catalog-entry"),
+            (self.no_direct, "no-direct-version"),
+            (self.no_missing, "catalog-entry-exists"),
+        ]
+        .into_iter()
+        .filter_map(|(set, rule)| set.then_some(rule))
+        .collect();
+
         if !self.only_rules.is_empty() {
-            Filter::Only(self.only_rules.clone())
-        } else if !self.exclude_rules.is_empty() {
-            Filter::Exclude(self.exclude_rules.clone())
+            Filter::Only(
+                self.only_rules
+                    .iter()
+                    .filter(|rule| !shortcuts.contains(&rule.as_str()))
+                    .cloned()
+                    .collect(),
+            )
+        } else if !self.exclude_rules.is_empty() || !shortcuts.is_empty() {
+            let mut excluded = self.exclude_rules.clone();
+            excluded.extend(shortcuts.into_iter().map(String::from));
+            Filter::Exclude(excluded)
         } else {
             Filter::None
         }
@@ -96,3 +388,16 @@ impl Args {
         }
     }
 }
+
+/// A percentage between 0 and 100 for `--min-coverage`; a trailing `%` is accepted.
+fn parse_percent(value: &str) -> Result<f64, String> {
+    let percent: f64 = value
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if (0.0..=100.0).contains(&percent) {
+        Ok(percent)
+    } else {
+        Err(format!("{value} is not between 0 and 100"))
+    }
+}
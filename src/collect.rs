@@ -1,17 +1,45 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use nodejs_semver::Range;
 
+use crate::config::Policy;
+use crate::error::LintError;
 use crate::packages::{
-    DependencyKind, Package, is_catalog_ref, is_special_protocol, parse_catalog_ref,
+    DependencyKind, Package, PackageLintConfig, PackageType, VersionKind, alias_target,
+    classify_version, is_special_protocol, malformed_catalog_ref, parse_catalog_ref,
+    workspace_ref_target,
 };
 use crate::rules::catalog_entry_exists::{CatalogEntryExistsIssue, MissingCatalog};
+use crate::rules::catalog_entry_is_workspace_package::CatalogEntryIsWorkspacePackageIssue;
+use crate::rules::catalog_overly_permissive::CatalogOverlyPermissiveIssue;
+use crate::rules::catalog_prerelease_version::{CatalogPrereleaseVersionIssue, prerelease_version};
+use crate::rules::conflicting_dependency_kinds::ConflictingDependencyKindsIssue;
+use crate::rules::dangling_workspace_ref::DanglingWorkspaceRefIssue;
+use crate::rules::dependency_name_whitespace::DependencyNameWhitespaceIssue;
+use crate::rules::duplicate_catalog_key::DuplicateCatalogKeyIssue;
+use crate::rules::duplicate_package_name::DuplicatePackageNameIssue;
+use crate::rules::empty_catalog::EmptyCatalogIssue;
+use crate::rules::incomplete_catalog::IncompleteCatalogIssue;
+use crate::rules::invalid_catalog_lint_config::InvalidCatalogLintConfigIssue;
+use crate::rules::malformed_catalog_ref::MalformedCatalogRefIssue;
 use crate::rules::no_direct_version::NoDirectVersionIssue;
 use crate::rules::no_uncataloged_dependency::NoUncatalogedDependencyIssue;
+use crate::rules::peer_catalog_consistency::PeerCatalogConsistencyIssue;
+use crate::rules::policy::PolicyIssue;
+use crate::rules::redundant_catalog_duplication::RedundantCatalogDuplicationIssue;
+use crate::rules::shared_dependency_uses_catalog::{
+    SharedDependencyUsesCatalogIssue, SharedViolation,
+};
 use crate::rules::unused_catalog_entry::UnusedCatalogEntryIssue;
-use crate::rules::{Filter, IssuesList};
-use crate::workspace::{CatalogEntry, PnpmWorkspaceYaml, WorkspaceCatalogs};
+use crate::rules::{Filter, Issue, IssuesList, RULE_NAMES};
+use crate::stats::{CatalogUsage, DependencySpread, EntryReport};
+use crate::workspace::{
+    CatalogEntry, PnpmWorkspaceYaml, WorkspaceCatalogs, parse_workspace, relative_to,
+};
 
 /// Describes a single version replacement for fixing no-direct-version.
 #[derive(Debug, Clone)]
@@ -37,31 +65,66 @@ pub struct FixActions {
     pub catalog_addition_replacements: Vec<VersionReplacement>,
 }
 
-pub fn collect_packages(root: &Path, workspace: &PnpmWorkspaceYaml) -> Result<Vec<Package>> {
-    let mut packages = Vec::new();
+/// Options controlling how packages are discovered and loaded.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Worker threads used to load package.json files (0 = one per CPU)
+    pub threads: usize,
+    /// Package directories to skip, as globs relative to the workspace root
+    pub ignored_paths: Vec<glob::Pattern>,
+}
+
+/// Find every package in the workspace and load its package.json.
+/// Loading is spread over worker threads; the result keeps discovery order.
+/// Also returns the `packages:` patterns that matched directories, none of which
+/// had a package.json (`--warn-empty-matches`).
+///
+/// Without a `packages:` list, the `workspaces` field of the root package.json is used.
+pub fn collect_packages(
+    root: &Path,
+    workspace: &PnpmWorkspaceYaml,
+    options: &LoadOptions,
+) -> std::result::Result<(Vec<Package>, Vec<String>), LintError> {
+    let mut dirs = Vec::new();
+    // The pattern each entry of `dirs` was found by
+    let mut sources = Vec::new();
+    let mut empty_matches = Vec::new();
 
     // Load root package.json
     let root_pkg_path = root.join("package.json");
+    let mut fallback_patterns = Vec::new();
     if root_pkg_path.exists() {
-        packages.push(
-            Package::load(root, true)
-                .with_context(|| format!("Failed to load root package at {}", root.display()))?,
-        );
+        dirs.push(root.to_path_buf());
+        sources.push(None);
+        if workspace.packages.is_empty() {
+            fallback_patterns = Package::load(root, true)?.inner.workspace_globs().to_vec();
+        }
     }
+    let patterns = if workspace.packages.is_empty() {
+        &fallback_patterns
+    } else {
+        &workspace.packages
+    };
 
     // Expand workspace package patterns
-    for pattern in &workspace.packages {
+    for pattern in patterns {
         // Skip negated patterns
         if pattern.starts_with('!') {
             continue;
         }
 
         let full_pattern = root.join(pattern).to_string_lossy().to_string();
-        let matches = glob::glob(&full_pattern)
-            .with_context(|| format!("Invalid glob pattern: {pattern}"))?;
+        let matches = glob::glob(&full_pattern).map_err(|source| LintError::InvalidGlob {
+            pattern: pattern.clone(),
+            source,
+        })?;
 
+        let (mut matched_dirs, mut matched_packages) = (0, 0);
         for entry in matches {
-            let entry = entry.with_context(|| format!("Glob error for pattern: {pattern}"))?;
+            let entry = entry.map_err(|e| LintError::Io {
+                path: e.path().to_path_buf(),
+                source: e.into_error(),
+            })?;
 
             // entry could be a directory or a file matching the glob
             let dir = if entry.is_dir() {
@@ -70,64 +133,322 @@ pub fn collect_packages(root: &Path, workspace: &PnpmWorkspaceYaml) -> Result<Ve
                 continue;
             };
 
+            matched_dirs += 1;
+
             // Skip if no package.json
             if !dir.join("package.json").exists() {
                 continue;
             }
+            matched_packages += 1;
 
             // Skip root (already added)
             if dir == root {
                 continue;
             }
 
-            packages.push(
-                Package::load(&dir, false)
-                    .with_context(|| format!("Failed to load package at {}", dir.display()))?,
-            );
+            // Skip directories matched by --ignore-path, without loading them
+            let relative = dir.strip_prefix(root).unwrap_or(&dir);
+            if options
+                .ignored_paths
+                .iter()
+                .any(|pattern| pattern.matches_path(relative))
+            {
+                continue;
+            }
+
+            dirs.push(dir);
+            sources.push(Some(pattern.clone()));
+        }
+
+        if matched_dirs > 0 && matched_packages == 0 {
+            empty_matches.push(pattern.clone());
+        }
+    }
+
+    let mut packages = load_packages(root, &dirs, options.threads)?;
+    for (pkg, pattern) in packages.iter_mut().zip(sources) {
+        pkg.pattern = pattern;
+    }
+    Ok((packages, empty_matches))
+}
+
+/// `packages:` patterns that look like package names rather than directory globs: they
+/// contain no slash and match no directory. pnpm finds nothing for them, without an error.
+pub fn package_name_patterns<'a>(root: &Path, workspace: &'a PnpmWorkspaceYaml) -> Vec<&'a str> {
+    workspace
+        .packages
+        .iter()
+        .filter(|pattern| !pattern.starts_with('!') && !pattern.contains('/'))
+        .filter(|pattern| {
+            let full_pattern = root.join(pattern).to_string_lossy().to_string();
+            // Invalid globs are reported by collect_packages
+            glob::glob(&full_pattern)
+                .is_ok_and(|mut matches| !matches.any(|entry| entry.is_ok_and(|p| p.is_dir())))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+fn load_packages(
+    root: &Path,
+    dirs: &[PathBuf],
+    threads: usize,
+) -> std::result::Result<Vec<Package>, LintError> {
+    let threads = match threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let chunk_size = dirs.len().div_ceil(threads).max(1);
+
+    let load = |dir: &PathBuf| Package::load(dir, dir == root);
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = dirs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(load)
+                        .collect::<std::result::Result<Vec<_>, _>>()
+                })
+            })
+            .collect();
+
+        let mut packages = Vec::with_capacity(dirs.len());
+        for worker in workers {
+            packages.extend(worker.join().expect("package loader panicked")?);
         }
+        Ok(packages)
+    })
+}
+
+/// Which packages the catalog-usage rules (no-direct-version, no-uncataloged-dependency) apply to.
+/// A package without a `private` field counts as public.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CatalogEnforce {
+    #[default]
+    All,
+    Public,
+    Private,
+}
+
+impl CatalogEnforce {
+    pub fn applies_to(self, pkg: &Package) -> bool {
+        match self {
+            CatalogEnforce::All => true,
+            CatalogEnforce::Public => !pkg.inner.private,
+            CatalogEnforce::Private => pkg.inner.private,
+        }
+    }
+}
+
+/// Options controlling which rules, packages, and dependencies are linted.
+#[derive(Clone, Default)]
+pub struct LintOptions {
+    pub rule_filter: Filter,
+    pub package_filter: Filter,
+    pub dependency_filter: Filter,
+    pub catalog_enforce: CatalogEnforce,
+    /// Dependencies exempt from no-direct-version; other rules still apply to them
+    pub allow_direct: Vec<String>,
+    /// Skip no-direct-version when the direct version equals a catalog's version
+    pub allow_matching_direct: bool,
+    /// User-defined policies from the config file
+    pub policies: Vec<Policy>,
+    /// Catalog entries exempt from unused-catalog-entry
+    pub reserved_entries: HashSet<CatalogEntry>,
+    /// Catalogs exempt from unused-catalog-entry (`default` for the default catalog)
+    pub ignore_unused_in: Vec<String>,
+    /// Catalogs to lint (`default` for the default catalog); empty = all
+    pub only_catalogs: Vec<String>,
+    /// Catalog versions reported by catalog-overly-permissive
+    pub permissive_specs: Vec<String>,
+    /// Dependencies exempt from catalog-prerelease-version
+    pub allow_prerelease: Vec<String>,
+    /// `[messages]` templates by rule name
+    pub message_templates: HashMap<String, String>,
+    /// Report warnings as errors, except from policies with an explicit level
+    pub strict: bool,
+    /// Run peer-catalog-consistency (opt-in)
+    pub peer_consistency: bool,
+    /// Base directory for paths shown in messages; None = absolute paths
+    pub relative_to: Option<PathBuf>,
+    /// Package directories outside the `--since` window; linted like excluded packages
+    pub unchanged_packages: HashSet<PathBuf>,
+    /// Packages sharing a dependency before shared-dependency-uses-catalog applies; None = off
+    pub catalog_threshold: Option<usize>,
+}
+
+impl LintOptions {
+    /// Whether `--catalog` selects a catalog (None = the default catalog).
+    pub fn is_catalog_selected(&self, catalog_name: Option<&str>) -> bool {
+        let catalog = catalog_name.unwrap_or("default");
+        self.only_catalogs.is_empty() || self.only_catalogs.iter().any(|c| c == catalog)
     }
+}
+
+/// Re-load the workspace from disk and check that every catalog: reference still resolves.
+/// Used after `--fix` to confirm the rewritten files didn't introduce broken references.
+pub fn verify_catalog_refs(
+    root: &Path,
+    options: &LintOptions,
+    load_options: &LoadOptions,
+) -> Result<IssuesList> {
+    let (workspace_yaml, catalogs) = parse_workspace(root)?;
+    let (packages, _) = collect_packages(root, &workspace_yaml, load_options)?;
+    let (issues, _, _) = collect_issues(
+        &packages,
+        &catalogs,
+        &LintOptions {
+            rule_filter: Filter::Only(vec!["catalog-entry-exists".to_string()]),
+            ..options.clone()
+        },
+    );
+    Ok(issues)
+}
 
-    Ok(packages)
+/// Load the workspace and count the packages using each catalog entry, for `catalog-report`.
+pub fn catalog_report(root: &Path) -> Result<Vec<EntryReport>> {
+    let (workspace_yaml, catalogs) = parse_workspace(root)?;
+    let (packages, _) = collect_packages(root, &workspace_yaml, &LoadOptions::default())?;
+    let (_, _, usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+    Ok(usage.entry_report(&catalogs))
 }
 
 pub fn collect_issues(
     packages: &[Package],
     catalogs: &WorkspaceCatalogs,
-    rule_filter: Filter,
-    package_filter: &Filter,
-    dependency_filter: &Filter,
-) -> (IssuesList, FixActions) {
-    let mut issues = IssuesList::new(rule_filter);
+    options: &LintOptions,
+) -> (IssuesList, FixActions, CatalogUsage) {
+    collect_issues_with(packages, catalogs, options, |_| {})
+}
+
+/// Like `collect_issues`, but hands each package's issues to `on_package` as soon as the
+/// package is done. Workspace-level issues (e.g. unused catalog entries) need the full pass
+/// and are only in the returned list.
+pub fn collect_issues_with(
+    packages: &[Package],
+    catalogs: &WorkspaceCatalogs,
+    options: &LintOptions,
+    mut on_package: impl FnMut(&[(PackageType, Box<dyn Issue>)]),
+) -> (IssuesList, FixActions, CatalogUsage) {
+    let mut issues = IssuesList::new(options.rule_filter.clone())
+        .with_message_templates(options.message_templates.clone());
+    if options.strict {
+        let pinned = options
+            .policies
+            .iter()
+            .filter(|policy| policy.level.is_some())
+            .map(|policy| policy.name.clone())
+            .collect();
+        issues = issues.with_strict(pinned);
+    }
+    let mut usage = CatalogUsage::default();
     let mut version_replacements = Vec::new();
     let mut catalog_additions_raw: Vec<(CatalogAddition, VersionReplacement)> = Vec::new();
 
     // Track used catalog entries for unused-catalog-entry rule
-    let mut used_entries = catalogs.all_entries();
+    let mut used_entries = UnusedEntries(
+        (!issues.is_rule_ignored("unused-catalog-entry")).then(|| catalogs.all_entries()),
+    );
+
+    // Names workspace: references can resolve to
+    let workspace_names: HashSet<&str> = packages
+        .iter()
+        .filter_map(|pkg| pkg.inner.name.as_deref())
+        .collect();
+
+    // Missing entries of named catalogs and the packages referencing them, for
+    // incomplete-catalog; gathered whether or not catalog-entry-exists is reported
+    let mut incomplete: BTreeMap<String, (BTreeSet<String>, HashSet<PackageType>)> =
+        BTreeMap::new();
+
+    let spread = options
+        .catalog_threshold
+        .map(|_| DependencySpread::collect(packages))
+        .unwrap_or_default();
 
     for pkg in packages {
-        let pkg_name = match &pkg.package_type {
-            crate::packages::PackageType::Root => "(root)".to_string(),
-            crate::packages::PackageType::Workspace(name) => name.clone(),
-        };
-        let is_ignored = package_filter.is_ignored(&pkg_name);
+        let first_issue = issues.len();
+        let local = package_lint_config(pkg, options);
+        issues.set_package_ignored_rules(&local.ignore_rules);
+        let pkg_name = pkg.package_type.to_string();
+        let is_ignored = options.package_filter.is_ignored(&pkg_name)
+            || options.unchanged_packages.contains(&pkg.path);
+        let enforce_catalog = options.catalog_enforce.applies_to(pkg);
+        if is_ignored {
+            issues.skip_package();
+        } else {
+            for problem in local.problems(|name| is_rule_or_policy(name, options)) {
+                issues.add(
+                    pkg.package_type.clone(),
+                    Box::new(InvalidCatalogLintConfigIssue { problem }),
+                );
+            }
+        }
 
         for dep in pkg.all_dependencies() {
-            if !is_ignored && dependency_filter.is_ignored(&dep.name) {
+            // A filtered-out dependency reports nothing, but its references still count
+            // as uses of catalog entries, like those of a filtered-out package
+            let dependency_ignored = options.dependency_filter.is_ignored(&dep.name)
+                || local.ignore_dependencies.contains(&dep.name);
+            if !is_ignored && dependency_ignored {
+                issues.skip_dependency();
+            }
+            let is_ignored = is_ignored || dependency_ignored;
+
+            // A padded name matches no catalog entry; say so instead of reporting the miss
+            if dep.name.trim() != dep.name {
+                if !is_ignored {
+                    issues.add(
+                        pkg.package_type.clone(),
+                        Box::new(DependencyNameWhitespaceIssue {
+                            dependency_name: dep.name.clone(),
+                            kind: dep.kind,
+                        }),
+                    );
+                }
                 continue;
             }
 
-            if is_catalog_ref(&dep.version) {
-                // Dependency uses catalog: protocol — check if the entry exists
-                let parsed = parse_catalog_ref(&dep.version);
-                if let Some(catalog_name) = parsed {
+            // User-defined policies; workspace: references never resolve through a catalog
+            if !is_ignored && !dep.version.starts_with("workspace:") {
+                for policy in &options.policies {
+                    if policy.applies_to(&dep) && !policy.is_satisfied_by(&dep.version) {
+                        issues.add(
+                            pkg.package_type.clone(),
+                            Box::new(PolicyIssue {
+                                policy: policy.name.clone(),
+                                level: policy.level(),
+                                dependency_name: dep.name.clone(),
+                                version: dep.version.clone(),
+                                kind: dep.kind,
+                                expected_ref: policy.expected_ref(),
+                            }),
+                        );
+                    }
+                }
+            }
+
+            match classify_version(&dep.version) {
+                VersionKind::Catalog(catalog_name) => {
+                    // References to catalogs outside --catalog still mark entries as used,
+                    // but are taken as valid
+                    let is_ignored =
+                        is_ignored || !options.is_catalog_selected(catalog_name.as_deref());
+                    // Dependency uses catalog: protocol — check if the entry exists
                     match &catalog_name {
                         None => {
                             // Default catalog reference
                             if catalogs.has_default_entry(&dep.name) {
                                 // Mark as used
-                                used_entries.retain(|e| {
-                                    !(e.catalog_name.is_none() && e.dependency_name == dep.name)
-                                });
+                                let entry = CatalogEntry {
+                                    catalog_name: None,
+                                    dependency_name: dep.name.clone(),
+                                };
+                                used_entries.mark_used(&entry);
+                                usage.record_catalog_ref(entry, &pkg.package_type);
                             } else if !is_ignored {
                                 issues.add(
                                     pkg.package_type.clone(),
@@ -156,87 +477,259 @@ pub fn collect_issues(
                                 }
                             } else if catalogs.has_named_entry(name, &dep.name) {
                                 // Mark as used
-                                used_entries.retain(|e| {
-                                    !(e.catalog_name.as_deref() == Some(name)
-                                        && e.dependency_name == dep.name)
-                                });
+                                let entry = CatalogEntry {
+                                    catalog_name: Some(name.clone()),
+                                    dependency_name: dep.name.clone(),
+                                };
+                                used_entries.mark_used(&entry);
+                                usage.record_catalog_ref(entry, &pkg.package_type);
                             } else if !is_ignored {
+                                let catalog_empty = catalogs
+                                    .named()
+                                    .get(name)
+                                    .is_some_and(|entries| entries.is_empty());
+                                // Empty catalogs have their own issue
+                                if !catalog_empty {
+                                    let (missing, referencing) =
+                                        incomplete.entry(name.clone()).or_default();
+                                    missing.insert(dep.name.clone());
+                                    referencing.insert(pkg.package_type.clone());
+                                }
                                 issues.add(
                                     pkg.package_type.clone(),
                                     Box::new(CatalogEntryExistsIssue {
                                         dependency_name: dep.name.clone(),
                                         catalog_ref: dep.version.clone(),
                                         kind: dep.kind,
-                                        missing: MissingCatalog::NamedEntry(name.clone()),
+                                        missing: MissingCatalog::NamedEntry {
+                                            catalog: name.clone(),
+                                            in_default: catalogs.has_default_entry(&dep.name),
+                                            catalog_empty,
+                                        },
                                     }),
                                 );
                             }
                         }
                     }
                 }
-            } else if !is_special_protocol(&dep.version) {
-                // Dependency uses a direct version — check if it's in any catalog
-                let found_in = catalogs.find_dependency(&dep.name);
-                if !found_in.is_empty() {
-                    // Mark matching catalog entries as used (the dependency exists,
-                    // it's just not using the catalog: protocol)
-                    for catalog_name in &found_in {
-                        used_entries.retain(|e| {
-                            !(e.catalog_name == *catalog_name && e.dependency_name == dep.name)
+                VersionKind::Direct if malformed_catalog_ref(&dep.version).is_some() => {
+                    // Wrong casing or stray whitespace/slashes — pnpm won't resolve it as
+                    // written, but the intended entry is still in use
+                    let suggestion = malformed_catalog_ref(&dep.version).unwrap_or_default();
+                    if let Some(catalog_name) = parse_catalog_ref(&suggestion) {
+                        used_entries.mark_used(&CatalogEntry {
+                            catalog_name,
+                            dependency_name: dep.name.clone(),
                         });
                     }
 
                     if !is_ignored {
-                        // Prefer default catalog, otherwise first named catalog
-                        let catalog_ref = if found_in.contains(&None) {
-                            "catalog:".to_string()
-                        } else {
-                            format!("catalog:{}", found_in[0].as_ref().unwrap())
-                        };
-
-                        version_replacements.push(VersionReplacement {
-                            package_path: pkg.path.clone(),
-                            dependency_name: dep.name.clone(),
-                            kind: dep.kind,
-                            catalog_ref,
-                        });
-
                         issues.add(
                             pkg.package_type.clone(),
-                            Box::new(NoDirectVersionIssue {
+                            Box::new(MalformedCatalogRefIssue {
+                                dependency_name: dep.name.clone(),
+                                version: dep.version.clone(),
+                                kind: dep.kind,
+                                suggestion,
+                            }),
+                        );
+                    }
+                }
+                VersionKind::Special("workspace:") => {
+                    if let Some(target) = workspace_ref_target(&dep.name, &dep.version)
+                        && !is_ignored
+                        && !workspace_names.contains(target)
+                    {
+                        issues.add(
+                            pkg.package_type.clone(),
+                            Box::new(DanglingWorkspaceRefIssue {
+                                dependency_name: dep.name.clone(),
+                                version: dep.version.clone(),
+                                kind: dep.kind,
+                                target: target.to_string(),
+                            }),
+                        );
+                    }
+                }
+                VersionKind::Special(_) => {}
+                VersionKind::Direct => {
+                    // Dependency uses a direct version — check if it's in any catalog
+                    let found_in = catalogs.find_dependency(&dep.name);
+
+                    if let Some(threshold) = options.catalog_threshold {
+                        let shared_by = spread.package_count(&dep.name);
+                        if !is_ignored && enforce_catalog && shared_by >= threshold {
+                            issues.add(
+                                pkg.package_type.clone(),
+                                Box::new(SharedDependencyUsesCatalogIssue {
+                                    dependency_name: dep.name.clone(),
+                                    version: dep.version.clone(),
+                                    kind: dep.kind,
+                                    shared_by,
+                                    threshold,
+                                    violation: if found_in.is_empty() {
+                                        SharedViolation::Uncataloged
+                                    } else {
+                                        SharedViolation::DirectVersion
+                                    },
+                                }),
+                            );
+                        }
+                    }
+
+                    if !found_in.is_empty() {
+                        // Mark matching catalog entries as used (the dependency exists,
+                        // it's just not using the catalog: protocol)
+                        let entries: Vec<CatalogEntry> = found_in
+                            .iter()
+                            .map(|catalog_name| CatalogEntry {
+                                catalog_name: catalog_name.clone(),
+                                dependency_name: dep.name.clone(),
+                            })
+                            .collect();
+                        for entry in &entries {
+                            used_entries.mark_used(entry);
+                        }
+                        usage.record_direct_ref(entries, &pkg.package_type);
+
+                        // With --allow-matching-direct, a version a catalog accepts is no drift
+                        let matches_catalog = options.allow_matching_direct
+                            && found_in.iter().any(|catalog_name| {
+                                catalogs
+                                    .accepted_versions(&CatalogEntry {
+                                        catalog_name: catalog_name.clone(),
+                                        dependency_name: dep.name.clone(),
+                                    })
+                                    .contains(&dep.version.as_str())
+                            });
+
+                        if !is_ignored
+                            && enforce_catalog
+                            && !options.allow_direct.contains(&dep.name)
+                            && !local.allow_direct.contains(&dep.name)
+                            && !matches_catalog
+                        {
+                            let issue = NoDirectVersionIssue {
                                 dependency_name: dep.name.clone(),
                                 version: dep.version.clone(),
                                 kind: dep.kind,
                                 available_in: found_in,
+                            };
+
+                            // A dependency in several catalogs is left for the user to pick
+                            if issue.fixable() && !local.ignores_rule(issue.name()) {
+                                let catalog_ref = match &issue.available_in[0] {
+                                    None => "catalog:".to_string(),
+                                    Some(name) => format!("catalog:{name}"),
+                                };
+                                version_replacements.push(VersionReplacement {
+                                    package_path: pkg.path.clone(),
+                                    dependency_name: dep.name.clone(),
+                                    kind: dep.kind,
+                                    catalog_ref,
+                                });
+                            }
+
+                            issues.add(pkg.package_type.clone(), Box::new(issue));
+                        }
+                    } else if !is_ignored && enforce_catalog {
+                        issues.add(
+                            pkg.package_type.clone(),
+                            Box::new(NoUncatalogedDependencyIssue {
+                                dependency_name: dep.name.clone(),
+                                version: dep.version.clone(),
+                                kind: dep.kind,
                             }),
                         );
+                        if !local.ignores_rule("no-uncataloged-dependency") {
+                            catalog_additions_raw.push((
+                                CatalogAddition {
+                                    dependency_name: dep.name.clone(),
+                                    version: dep.version.clone(),
+                                },
+                                VersionReplacement {
+                                    package_path: pkg.path.clone(),
+                                    dependency_name: dep.name.clone(),
+                                    kind: dep.kind,
+                                    catalog_ref: "catalog:".to_string(),
+                                },
+                            ));
+                        }
                     }
-                } else if !is_ignored {
-                    issues.add(
-                        pkg.package_type.clone(),
-                        Box::new(NoUncatalogedDependencyIssue {
-                            dependency_name: dep.name.clone(),
-                            version: dep.version.clone(),
-                            kind: dep.kind,
-                        }),
-                    );
-                    catalog_additions_raw.push((
-                        CatalogAddition {
-                            dependency_name: dep.name.clone(),
-                            version: dep.version.clone(),
-                        },
-                        VersionReplacement {
-                            package_path: pkg.path.clone(),
-                            dependency_name: dep.name.clone(),
-                            kind: dep.kind,
-                            catalog_ref: "catalog:".to_string(),
-                        },
-                    ));
                 }
             }
         }
+
+        if !is_ignored {
+            for issue in conflicting_kinds_issues(pkg, &options.dependency_filter) {
+                if !local.ignore_dependencies.contains(&issue.dependency_name) {
+                    issues.add(pkg.package_type.clone(), Box::new(issue));
+                }
+            }
+        }
+
+        if options.peer_consistency && !is_ignored {
+            for issue in peer_consistency_issues(pkg, catalogs, &options.dependency_filter) {
+                if !local.ignore_dependencies.contains(&issue.dependency_name) {
+                    issues.add(pkg.package_type.clone(), Box::new(issue));
+                }
+            }
+        }
+
+        on_package(issues.since(first_issue));
+    }
+    issues.set_package_ignored_rules(&[]);
+
+    // Two directories claiming the same name collide in pnpm
+    let mut dirs_by_name: IndexMap<&str, Vec<PathBuf>> = IndexMap::new();
+    for pkg in packages {
+        if let Some(name) = pkg.inner.name.as_deref() {
+            let path = match &options.relative_to {
+                Some(base) => relative_to(&pkg.path, base),
+                None => pkg.path.clone(),
+            };
+            dirs_by_name.entry(name).or_default().push(path);
+        }
+    }
+    for (name, paths) in dirs_by_name {
+        if paths.len() > 1 && !options.package_filter.is_ignored(name) {
+            issues.add(
+                PackageType::Workspace(name.to_string()),
+                Box::new(DuplicatePackageNameIssue {
+                    package_name: name.to_string(),
+                    paths,
+                }),
+            );
+        }
+    }
+
+    // Roll up missing entries of a named catalog into one suggestion once several packages
+    // run into its gaps; with one package its own errors already say it all
+    for (catalog_name, (missing, referencing)) in incomplete {
+        if referencing.len() < 2 {
+            continue;
+        }
+        issues.add(
+            PackageType::Catalog,
+            Box::new(IncompleteCatalogIssue {
+                catalog_name,
+                missing: missing.into_iter().collect(),
+                packages: referencing.len(),
+            }),
+        );
     }
 
+    // Reserved entries are kept on purpose, so they're never unused (nor removed by --fix)
+    let mut used_entries = used_entries.0.unwrap_or_default();
+    for entry in &options.reserved_entries {
+        used_entries.remove(entry);
+    }
+    used_entries.retain(|e| {
+        let catalog = e.catalog_name.as_deref().unwrap_or("default");
+        !options.ignore_unused_in.iter().any(|c| c == catalog)
+            && options.is_catalog_selected(e.catalog_name.as_deref())
+    });
+
     // Collect unused entries before emitting warnings
     let unused_entries: Vec<CatalogEntry> = if issues.is_rule_ignored("unused-catalog-entry") {
         Vec::new()
@@ -251,49 +744,61 @@ pub fn collect_issues(
         version_replacements
     };
 
+    // Dependencies packages use with different versions: --fix can't pick a catalog version
+    let mut addition_versions: HashMap<&str, &str> = HashMap::new();
+    let mut conflicting_additions: HashSet<String> = HashSet::new();
+    for (addition, _) in &catalog_additions_raw {
+        let version = addition_versions
+            .entry(&addition.dependency_name)
+            .or_insert(&addition.version);
+        if *version != addition.version {
+            conflicting_additions.insert(addition.dependency_name.clone());
+        }
+    }
+    issues.mark_unfixable("no-uncataloged-dependency", &conflicting_additions);
+
     // Deduplicate catalog additions: same dep+version → 1 addition, N replacements.
     // If versions conflict for the same dep → skip entirely (not auto-fixable).
-    let (catalog_additions, catalog_addition_replacements) = if issues
-        .is_rule_ignored("no-uncataloged-dependency")
-    {
-        (Vec::new(), Vec::new())
-    } else {
-        let mut seen: HashMap<String, String> = HashMap::new();
-        let mut conflicting: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for (addition, _) in &catalog_additions_raw {
-            match seen.entry(addition.dependency_name.clone()) {
-                std::collections::hash_map::Entry::Vacant(e) => {
-                    e.insert(addition.version.clone());
+    let (catalog_additions, catalog_addition_replacements) =
+        if issues.is_rule_ignored("no-uncataloged-dependency") {
+            (Vec::new(), Vec::new())
+        } else {
+            let mut additions = Vec::new();
+            let mut replacements = Vec::new();
+            let mut added_deps: HashSet<String> = HashSet::new();
+            for (addition, replacement) in catalog_additions_raw {
+                if conflicting_additions.contains(&addition.dependency_name) {
+                    continue;
                 }
-                std::collections::hash_map::Entry::Occupied(e) => {
-                    if e.get() != &addition.version {
-                        conflicting.insert(addition.dependency_name.clone());
-                    }
+                replacements.push(replacement);
+                if added_deps.insert(addition.dependency_name.clone()) {
+                    additions.push(addition);
                 }
             }
-        }
-
-        let mut additions = Vec::new();
-        let mut replacements = Vec::new();
-        let mut added_deps: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for (addition, replacement) in catalog_additions_raw {
-            if conflicting.contains(&addition.dependency_name) {
-                continue;
-            }
-            replacements.push(replacement);
-            if added_deps.insert(addition.dependency_name.clone()) {
-                additions.push(addition);
-            }
-        }
-        (additions, replacements)
-    };
+            (additions, replacements)
+        };
 
     // Emit unused catalog entry warnings
     for entry in &used_entries {
-        if let Some(version) = catalogs.get_version(entry) {
+        if let Some(version) = catalogs.display_version(entry) {
             issues.add(
-                crate::packages::PackageType::Root,
+                PackageType::Catalog,
                 Box::new(UnusedCatalogEntryIssue {
+                    dependency_name: entry.dependency_name.clone(),
+                    catalog_name: entry.catalog_name.clone(),
+                    version,
+                }),
+            );
+        }
+    }
+
+    for entry in catalogs.duplicate_keys() {
+        if options.is_catalog_selected(entry.catalog_name.as_deref())
+            && let Some(version) = catalogs.get_version(entry)
+        {
+            issues.add(
+                PackageType::Catalog,
+                Box::new(DuplicateCatalogKeyIssue {
                     dependency_name: entry.dependency_name.clone(),
                     catalog_name: entry.catalog_name.clone(),
                     version: version.to_string(),
@@ -302,6 +807,109 @@ pub fn collect_issues(
         }
     }
 
+    // Emit warnings for named catalogs declared without entries
+    for (catalog_name, entries) in catalogs.named() {
+        if entries.is_empty() && options.is_catalog_selected(Some(catalog_name)) {
+            issues.add(
+                PackageType::Catalog,
+                Box::new(EmptyCatalogIssue {
+                    catalog_name: catalog_name.clone(),
+                }),
+            );
+        }
+    }
+
+    // Entries of the catalogs selected with --catalog, for the catalog-level rules below
+    let selected_entries: Vec<_> = catalogs
+        .entries()
+        .filter(|(catalog_name, _, _)| {
+            options.is_catalog_selected(catalog_name.map(String::as_str))
+        })
+        .collect();
+    // Git and tarball URLs aren't version ranges, so the rules about ranges skip them
+    let range_entries: Vec<_> = selected_entries
+        .iter()
+        .filter(|(_, _, version)| !is_special_protocol(version))
+        .collect();
+
+    // Emit warnings for unbounded catalog versions, e.g. "*" or "latest", also behind npm: aliases
+    for &&(catalog_name, dependency_name, version) in &range_entries {
+        let (_, range) = alias_target(dependency_name, version);
+        if options
+            .permissive_specs
+            .iter()
+            .any(|spec| spec == range.trim())
+        {
+            issues.add(
+                PackageType::Catalog,
+                Box::new(CatalogOverlyPermissiveIssue {
+                    dependency_name: dependency_name.clone(),
+                    catalog_name: catalog_name.cloned(),
+                    version: version.clone(),
+                }),
+            );
+        }
+    }
+
+    // Emit warnings for catalog entries pinning pre-release or build-metadata versions
+    for &&(catalog_name, dependency_name, version) in &range_entries {
+        if options.allow_prerelease.contains(dependency_name) {
+            continue;
+        }
+        let (_, range) = alias_target(dependency_name, version);
+        if let Some(pinned) = prerelease_version(range) {
+            issues.add(
+                PackageType::Catalog,
+                Box::new(CatalogPrereleaseVersionIssue {
+                    dependency_name: dependency_name.clone(),
+                    catalog_name: catalog_name.cloned(),
+                    version: version.clone(),
+                    pre_release: pinned.is_prerelease(),
+                }),
+            );
+        }
+    }
+
+    // Emit warnings for catalog entries naming a package of this workspace
+    for &(catalog_name, dependency_name, _) in &selected_entries {
+        if workspace_names.contains(dependency_name.as_str()) {
+            issues.add(
+                PackageType::Catalog,
+                Box::new(CatalogEntryIsWorkspacePackageIssue {
+                    dependency_name: dependency_name.clone(),
+                    catalog_name: catalog_name.cloned(),
+                }),
+            );
+        }
+    }
+
+    // Emit redundant duplication warnings: same dependency, same version, several catalogs
+    let mut pinned: BTreeMap<(String, String), Vec<Option<String>>> = BTreeMap::new();
+    for entry in catalogs.all_entries() {
+        if let Some(version) = catalogs.get_version(&entry) {
+            pinned
+                .entry((entry.dependency_name.clone(), version.to_string()))
+                .or_default()
+                .push(entry.catalog_name);
+        }
+    }
+    for ((dependency_name, version), mut in_catalogs) in pinned {
+        let selected = in_catalogs
+            .iter()
+            .any(|catalog| options.is_catalog_selected(catalog.as_deref()));
+        if in_catalogs.len() > 1 && selected {
+            in_catalogs.sort();
+            issues.add(
+                PackageType::Catalog,
+                Box::new(RedundantCatalogDuplicationIssue {
+                    dependency_name,
+                    version,
+                    catalogs: in_catalogs,
+                }),
+            );
+        }
+    }
+
     (
         issues,
         FixActions {
@@ -310,13 +918,148 @@ pub fn collect_issues(
             catalog_additions,
             catalog_addition_replacements,
         },
+        usage,
     )
 }
 
+/// Catalog entries no package has referenced yet. Nothing is tracked when
+/// `unused-catalog-entry` is off, which saves the bookkeeping on large workspaces.
+struct UnusedEntries(Option<HashSet<CatalogEntry>>);
+
+impl UnusedEntries {
+    fn mark_used(&mut self, entry: &CatalogEntry) {
+        if let Some(entries) = &mut self.0 {
+            entries.remove(entry);
+        }
+    }
+}
+
+/// A package's own exceptions: its `catalogLint` field and `"//catalogLintIgnore"` key.
+fn package_lint_config(pkg: &Package, options: &LintOptions) -> PackageLintConfig {
+    pkg.inner
+        .catalog_lint
+        .with_inline_ignores(&pkg.inner.inline_ignores, |name| {
+            is_rule_or_policy(name, options)
+        })
+}
+
+fn is_rule_or_policy(name: &str, options: &LintOptions) -> bool {
+    RULE_NAMES.contains(&name) || options.policies.iter().any(|p| p.name == name)
+}
+
+/// Names from `requested` that match no loaded package, as shown in reports
+/// (`(root)` for the workspace root).
+pub fn unknown_packages<'a>(packages: &[Package], requested: &'a [String]) -> Vec<&'a str> {
+    let known: HashSet<String> = packages
+        .iter()
+        .map(|pkg| pkg.package_type.to_string())
+        .collect();
+    requested
+        .iter()
+        .filter(|name| !known.contains(name.as_str()))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Group a package's dependencies by name and report those declared with different version
+/// strings in more than one field. Peer ranges are usually wider on purpose, so
+/// peerDependencies are left out.
+fn conflicting_kinds_issues(
+    pkg: &Package,
+    dependency_filter: &Filter,
+) -> Vec<ConflictingDependencyKindsIssue> {
+    let mut by_name: IndexMap<String, Vec<(DependencyKind, String)>> = IndexMap::new();
+    for dep in pkg.all_dependencies() {
+        if dep.kind != DependencyKind::PeerDependencies && !dependency_filter.is_ignored(&dep.name)
+        {
+            by_name
+                .entry(dep.name)
+                .or_default()
+                .push((dep.kind, dep.version));
+        }
+    }
+    by_name
+        .into_iter()
+        .filter(|(_, declarations)| {
+            declarations
+                .iter()
+                .any(|(_, version)| *version != declarations[0].1)
+        })
+        .map(
+            |(dependency_name, declarations)| ConflictingDependencyKindsIssue {
+                dependency_name,
+                declarations,
+            },
+        )
+        .collect()
+}
+
+/// The semver range a dependency version resolves to: catalog: references are looked up,
+/// direct versions are taken as-is. `None` for special protocols, missing entries, and
+/// anything that isn't a valid range.
+fn resolved_range(dep_name: &str, version: &str, catalogs: &WorkspaceCatalogs) -> Option<String> {
+    let range = match classify_version(version) {
+        VersionKind::Catalog(catalog_name) => catalogs
+            .get_version(&CatalogEntry {
+                catalog_name,
+                dependency_name: dep_name.to_string(),
+            })?
+            .to_string(),
+        VersionKind::Direct => version.to_string(),
+        VersionKind::Special(_) => return None,
+    };
+    let (_, range) = alias_target(dep_name, &range);
+    Range::parse(range).is_ok().then(|| range.to_string())
+}
+
+/// Check each catalog: peer against the package's other declarations of the same dependency.
+fn peer_consistency_issues(
+    pkg: &Package,
+    catalogs: &WorkspaceCatalogs,
+    dependency_filter: &Filter,
+) -> Vec<PeerCatalogConsistencyIssue> {
+    let deps = pkg.all_dependencies();
+    let mut found = Vec::new();
+    for peer in deps.iter().filter(|dep| {
+        dep.kind == DependencyKind::PeerDependencies
+            && matches!(classify_version(&dep.version), VersionKind::Catalog(_))
+            && !dependency_filter.is_ignored(&dep.name)
+    }) {
+        let Some(peer_range) = resolved_range(&peer.name, &peer.version, catalogs) else {
+            continue;
+        };
+        for other in deps
+            .iter()
+            .filter(|dep| dep.name == peer.name && dep.kind != DependencyKind::PeerDependencies)
+        {
+            let Some(other_range) = resolved_range(&other.name, &other.version, catalogs) else {
+                continue;
+            };
+            let overlaps = match (Range::parse(&peer_range), Range::parse(&other_range)) {
+                (Ok(peer), Ok(other)) => peer.allows_any(&other),
+                _ => true,
+            };
+            if !overlaps {
+                found.push(PeerCatalogConsistencyIssue {
+                    dependency_name: peer.name.clone(),
+                    peer_ref: peer.version.clone(),
+                    peer_range: peer_range.clone(),
+                    other_kind: other.kind,
+                    other_version: other.version.clone(),
+                    other_range,
+                });
+            }
+        }
+    }
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::packages::{Package, PackageJson, PackageType};
+    use crate::rules::IssueLevel;
+    use crate::workspace::parse_workspace_file;
     use indexmap::IndexMap;
     use std::path::PathBuf;
 
@@ -331,10 +1074,9 @@ mod tests {
             inner: PackageJson {
                 name: Some(name.to_string()),
                 dependencies,
-                dev_dependencies: IndexMap::new(),
-                peer_dependencies: IndexMap::new(),
-                optional_dependencies: IndexMap::new(),
+                ..Default::default()
             },
+            pattern: None,
         }
     }
 
@@ -343,398 +1085,2242 @@ mod tests {
         for (k, v) in default {
             map.insert(k.to_string(), v.to_string());
         }
-        WorkspaceCatalogs {
-            default: map,
-            named: IndexMap::new(),
-        }
+        WorkspaceCatalogs::new(map, IndexMap::new())
     }
 
     #[test]
-    fn direct_version_marks_catalog_entry_as_used() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+    fn allow_matching_direct_reports_only_mismatches() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "^18.2.0"), ("lodash", "^4.17.0")],
+        )];
 
-        let (issues, _fix) = collect_issues(
+        let (issues, fix, usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                allow_matching_direct: true,
+                ..Default::default()
+            },
         );
 
-        // Should report no-direct-version error but NOT unused-catalog-entry
-        assert_eq!(issues.errors_count(), 1);
-        assert_eq!(issues.warnings_count(), 0);
-
-        let (_, issue) = issues.iter().next().unwrap();
-        assert_eq!(issue.name(), "no-direct-version");
+        let reported: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.dependency()))
+            .collect();
+        assert_eq!(reported, vec![("no-direct-version", Some("lodash"))]);
+        assert_eq!(fix.version_replacements.len(), 1);
+        assert_eq!(fix.version_replacements[0].dependency_name, "lodash");
+        // The matching direct version is still a use of the entry
+        assert!(fix.unused_entries.is_empty());
+        let react = usage.get(&CatalogEntry {
+            catalog_name: None,
+            dependency_name: "react".to_string(),
+        });
+        assert_eq!(react.direct_packages, 1);
     }
 
     #[test]
-    fn direct_version_no_false_unused_when_rule_excluded() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+    fn message_templates_replace_messages_of_their_rule() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("leftpad", "^1.0.0")]);
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "^18.2.0"), ("vite", "catalog:")],
+        )];
 
-        let (issues, _fix) = collect_issues(
+        let (issues, _, _) = collect_issues(
             &packages,
             &catalogs,
-            Filter::Exclude(vec!["no-direct-version".to_string()]),
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                message_templates: HashMap::from([
+                    (
+                        "no-direct-version".to_string(),
+                        "{message} See https://wiki.example.com/catalogs {".to_string(),
+                    ),
+                    (
+                        "catalog-entry-exists".to_string(),
+                        "[{rule}] {package}: add {dependency} to the {catalog} catalog ({kind}, {unknown}".to_string(),
+                    ),
+                ]),
+                ..Default::default()
+            },
         );
 
-        // With no-direct-version excluded, there should be zero issues —
-        // the catalog entry is still considered used
-        assert!(issues.is_empty());
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react' uses \"^18.2.0\" in dependencies but is available in catalog: default. Use \"catalog:\" instead. See https://wiki.example.com/catalogs {",
+                "[catalog-entry-exists] app: add vite to the default catalog (dependencies, {unknown}",
+                "'leftpad' (\"^1.0.0\") in the default catalog is never referenced",
+            ]
+        );
     }
 
     #[test]
-    fn returns_unused_entries() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
-        let packages = vec![make_package("app", vec![("react", "catalog:")])];
+    fn strict_promotes_warnings_except_pinned_policies() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("leftpad", "^1.0.0")]);
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "catalog:"), ("vitest", "^1.0.0")],
+        )];
+        let policy: Policy = toml::from_str(
+            r#"
+name = "tools-from-catalog"
+dependency = "vitest"
+catalog = "default"
+level = "warn"
+"#,
+        )
+        .unwrap();
+        let options = LintOptions {
+            rule_filter: Filter::Exclude(vec!["no-uncataloged-dependency".to_string()]),
+            policies: vec![policy],
+            ..Default::default()
+        };
 
-        let (_issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+        let levels = |strict| -> Vec<(String, IssueLevel)> {
+            let (issues, _, _) = collect_issues(
+                &packages,
+                &catalogs,
+                &LintOptions {
+                    strict,
+                    ..options.clone()
+                },
+            );
+            issues
+                .iter()
+                .map(|(_, i)| (i.name().to_string(), i.level()))
+                .collect()
+        };
+
+        assert_eq!(
+            levels(false),
+            vec![
+                ("tools-from-catalog".to_string(), IssueLevel::Warning),
+                ("unused-catalog-entry".to_string(), IssueLevel::Warning),
+            ]
+        );
+        assert_eq!(
+            levels(true),
+            vec![
+                ("tools-from-catalog".to_string(), IssueLevel::Warning),
+                ("unused-catalog-entry".to_string(), IssueLevel::Error),
+            ]
         );
+    }
 
-        assert_eq!(fix.unused_entries.len(), 1);
-        assert_eq!(fix.unused_entries[0].dependency_name, "lodash");
-        assert_eq!(fix.unused_entries[0].catalog_name, None);
+    #[test]
+    fn whitespace_in_dependency_name_reported_instead_of_missing_entry() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package(
+            "app",
+            vec![("react ", "catalog:"), (" lodash", "^4.17.21")],
+        )];
+
+        let (issues, fix, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<(&str, String)> = issues
+            .iter()
+            .map(|(_, i)| (i.name(), i.message()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "dependency-name-whitespace",
+                    "\"react \" in dependencies has leading or trailing whitespace in its name; did you mean 'react'?".to_string()
+                ),
+                (
+                    "dependency-name-whitespace",
+                    "\" lodash\" in dependencies has leading or trailing whitespace in its name; did you mean 'lodash'?".to_string()
+                ),
+                // The padded key doesn't count as a use of the entry
+                (
+                    "unused-catalog-entry",
+                    "'react' (\"^18.2.0\") in the default catalog is never referenced".to_string()
+                ),
+            ]
+        );
+        assert!(fix.version_replacements.is_empty());
+        assert!(fix.catalog_additions.is_empty());
     }
 
     #[test]
-    fn excluded_package_deps_still_mark_catalog_entries_as_used() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+    fn catalog_entry_for_workspace_package_reported() {
+        let catalogs = make_catalogs(vec![("@acme/ui", "^1.0.0"), ("react", "^18.2.0")]);
         let packages = vec![
-            make_package("app", vec![("react", "catalog:")]),
-            make_package("excluded-pkg", vec![("lodash", "catalog:")]),
+            make_package("@acme/ui", vec![("react", "catalog:")]),
+            make_package("app", vec![("@acme/ui", "catalog:")]),
         ];
 
-        let (issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::Exclude(vec!["excluded-pkg".to_string()]),
-            &Filter::None,
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<(&PackageType, &str, String)> = issues
+            .iter()
+            .map(|(pkg, i)| (pkg, i.name(), i.message()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![(
+                &PackageType::Catalog,
+                "catalog-entry-is-workspace-package",
+                "'@acme/ui' in the default catalog is also a workspace package. Reference it with \"workspace:*\" instead.".to_string()
+            )]
         );
-
-        // lodash should NOT be reported as unused — excluded-pkg references it
-        assert!(fix.unused_entries.is_empty());
-        // No issues from excluded-pkg should be reported
-        assert_eq!(issues.errors_count(), 0);
-        assert_eq!(issues.warnings_count(), 0);
     }
 
     #[test]
-    fn unused_entries_empty_when_rule_excluded() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
-        let packages = vec![make_package("app", vec![("react", "catalog:")])];
+    fn matching_direct_version_reported_without_flag() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
 
-        let (_issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::Exclude(vec!["unused-catalog-entry".to_string()]),
-            &Filter::None,
-            &Filter::None,
-        );
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
 
-        assert!(fix.unused_entries.is_empty());
+        assert_eq!(issues.errors_count(), 1);
     }
 
     #[test]
-    fn only_runs_specified_rules() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+    fn allow_direct_suppresses_only_no_direct_version() {
+        let catalogs = make_catalogs(vec![("eslint", "^9.0.0"), ("react", "^18.2.0")]);
+        let packages = vec![make_package(
+            "app",
+            vec![
+                ("eslint", "^9.1.0"),
+                ("react", "catalog:"),
+                ("lodash", "catalog:"),
+            ],
+        )];
 
-        let (issues, _fix) = collect_issues(
+        let (issues, fix, _usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::Only(vec!["no-direct-version".to_string()]),
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                allow_direct: vec!["eslint".to_string(), "lodash".to_string()],
+                ..Default::default()
+            },
         );
 
-        // Only no-direct-version should be reported, unused-catalog-entry should be filtered
-        assert_eq!(issues.errors_count(), 1);
+        // eslint is exempt and still counts as used; lodash's broken catalog: ref is reported
+        assert!(fix.version_replacements.is_empty());
         assert_eq!(issues.warnings_count(), 0);
-
+        assert_eq!(issues.errors_count(), 1);
         let (_, issue) = issues.iter().next().unwrap();
-        assert_eq!(issue.name(), "no-direct-version");
+        assert_eq!(issue.name(), "catalog-entry-exists");
     }
 
     #[test]
-    fn only_excludes_unspecified_rules() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+    fn package_catalog_lint_field_applies_to_that_package_only() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("vue", "^3.4.0")]);
+        let mut example = make_package("example", vec![]);
+        example.inner = serde_json::from_str(
+            r#"{
+                "name": "example",
+                "dependencies": { "react": "^18.0.0", "vue": "^3.0.0", "lodash": "^4.17.21" },
+                "catalogLint": {
+                    "allowDirect": ["react"],
+                    "ignoreRules": ["no-uncataloged-dependency"]
+                }
+            }"#,
+        )
+        .unwrap();
+        let packages = vec![
+            example,
+            make_package("app", vec![("react", "^18.0.0"), ("lodash", "^4.17.21")]),
+        ];
 
-        let (issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::Only(vec!["unused-catalog-entry".to_string()]),
-            &Filter::None,
-            &Filter::None,
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<String> = issues
+            .iter()
+            .map(|(pkg, issue)| format!("{pkg}: {} {}", issue.name(), issue.dependency().unwrap()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                "example: no-direct-version vue",
+                "app: no-direct-version react",
+                "app: no-uncataloged-dependency lodash",
+            ]
+        );
+        assert_eq!(issues.suppressed_count(), 1);
+        // Only app's lodash is added to the catalog
+        assert_eq!(fix.catalog_addition_replacements.len(), 1);
+        assert_eq!(
+            fix.catalog_addition_replacements[0].package_path,
+            packages[1].path
         );
-
-        // no-direct-version should be filtered out, only unused-catalog-entry should remain
-        assert_eq!(issues.errors_count(), 0);
-        assert_eq!(issues.warnings_count(), 1);
-        assert_eq!(fix.unused_entries.len(), 1);
     }
 
     #[test]
-    fn collects_version_replacements_for_direct_versions() {
+    fn unreadable_or_unknown_package_exceptions_are_reported() {
         let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+        let package = |name: &str, catalog_lint: &str| {
+            let mut pkg = make_package(name, vec![]);
+            pkg.inner = serde_json::from_str(&format!(
+                r#"{{ "name": "{name}", "dependencies": {{ "react": "^18.0.0" }}, "catalogLint": {catalog_lint} }}"#
+            ))
+            .unwrap();
+            pkg
+        };
+        let packages = vec![
+            package("typo", r#"{ "ignoreRules": ["no-direct-versions"] }"#),
+            package("malformed", r#"{ "allowDirect": "react" }"#),
+        ];
 
-        let (_issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<String> = issues
+            .iter()
+            .map(|(pkg, issue)| format!("{pkg}: {} {}", issue.name(), issue.message()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                "typo: invalid-catalog-lint-config \"catalogLint.ignoreRules\" lists 'no-direct-versions', which is not a rule or policy",
+                "typo: no-direct-version 'react' uses \"^18.0.0\" in dependencies but is available in catalog: default. Use \"catalog:\" instead.",
+                "malformed: invalid-catalog-lint-config \"catalogLint\" is invalid and was ignored: invalid type: string \"react\", expected a sequence",
+                "malformed: no-direct-version 'react' uses \"^18.0.0\" in dependencies but is available in catalog: default. Use \"catalog:\" instead.",
+            ]
         );
+    }
 
+    #[test]
+    fn inline_ignore_key_skips_rules_and_dependencies() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("vue", "^3.4.0")]);
+        let mut example = make_package("example", vec![]);
+        example.inner = serde_json::from_str(
+            r#"{
+                "name": "example",
+                "dependencies": {
+                    "react": "^18.0.0",
+                    "vue": "^3.0.0",
+                    "lodash": "^4.17.21",
+                    "zod": "catalog:"
+                },
+                "//catalogLintIgnore": "no-uncataloged-dependency, dep:vue, no-direct-versions"
+            }"#,
+        )
+        .unwrap();
+        let packages = vec![example];
+
+        let (issues, fix, usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<String> = issues
+            .iter()
+            .map(|(_, issue)| {
+                format!(
+                    "{} {}",
+                    issue.name(),
+                    issue.dependency().unwrap_or_default()
+                )
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                "invalid-catalog-lint-config ",
+                "no-direct-version react",
+                "catalog-entry-exists zod"
+            ]
+        );
+        assert_eq!(
+            issues.iter().next().unwrap().1.message(),
+            "\"//catalogLintIgnore\" lists 'no-direct-versions', which is not a rule or policy; \
+             write a dependency as 'dep:no-direct-versions'"
+        );
+        assert_eq!(issues.suppressed_count(), 1);
+        assert!(fix.catalog_additions.is_empty());
         assert_eq!(fix.version_replacements.len(), 1);
-        assert_eq!(fix.version_replacements[0].dependency_name, "react");
-        assert_eq!(fix.version_replacements[0].catalog_ref, "catalog:");
+        // An ignored dependency still uses its catalog entry
+        assert!(fix.unused_entries.is_empty());
+        let vue = usage.get(&CatalogEntry {
+            catalog_name: None,
+            dependency_name: "vue".to_string(),
+        });
+        assert_eq!(vue.direct_packages, 1);
     }
 
     #[test]
-    fn version_replacements_prefer_default_catalog() {
-        let mut named = IndexMap::new();
-        let mut legacy = IndexMap::new();
-        legacy.insert("react".to_string(), "^16.0.0".to_string());
-        named.insert("legacy".to_string(), legacy);
-
-        let catalogs = WorkspaceCatalogs {
-            default: {
-                let mut m = IndexMap::new();
-                m.insert("react".to_string(), "^18.2.0".to_string());
-                m
-            },
-            named,
-        };
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+    fn collect_issues_with_reports_each_package_when_done() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("leftpad", "^1.0.0")]);
+        let packages = vec![
+            make_package("app", vec![("react", "^18.2.0")]),
+            make_package("lib", vec![]),
+            make_package("web", vec![("react", "^18.2.0"), ("vue", "catalog:")]),
+        ];
 
-        let (_issues, fix) = collect_issues(
+        let mut seen = Vec::new();
+        let (issues, _fix, _usage) = collect_issues_with(
             &packages,
             &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+            &LintOptions::default(),
+            |pkg_issues| {
+                seen.push(
+                    pkg_issues
+                        .iter()
+                        .map(|(pkg, issue)| format!("{pkg}: {}", issue.name()))
+                        .collect::<Vec<_>>(),
+                );
+            },
         );
 
-        assert_eq!(fix.version_replacements.len(), 1);
-        assert_eq!(fix.version_replacements[0].catalog_ref, "catalog:");
+        assert_eq!(
+            seen,
+            vec![
+                vec!["app: no-direct-version"],
+                vec![],
+                vec!["web: no-direct-version", "web: catalog-entry-exists"],
+            ]
+        );
+        // The unused-catalog-entry warning only shows up once every package is done
+        assert_eq!(issues.len(), 4);
+        assert_eq!(issues.since(3)[0].1.name(), "unused-catalog-entry");
     }
 
     #[test]
-    fn version_replacements_use_named_catalog_when_no_default() {
-        let mut named = IndexMap::new();
-        let mut legacy = IndexMap::new();
-        legacy.insert("react".to_string(), "^16.0.0".to_string());
-        named.insert("legacy".to_string(), legacy);
+    fn duplicate_package_name_detected() {
+        let catalogs = make_catalogs(vec![]);
+        let mut copy = make_package("@acme/utils", vec![]);
+        copy.path = PathBuf::from("/fake/libs/utils");
+        let packages = vec![
+            make_package("@acme/utils", vec![]),
+            make_package("app", vec![]),
+            copy,
+        ];
 
-        let catalogs = WorkspaceCatalogs {
-            default: IndexMap::new(),
-            named,
-        };
-        let packages = vec![make_package("app", vec![("react", "^16.0.0")])];
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
 
-        let (_issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+        assert_eq!(issues.errors_count(), 1);
+        let (pkg, issue) = issues.iter().next().unwrap();
+        assert_eq!(*pkg, PackageType::Workspace("@acme/utils".to_string()));
+        assert_eq!(issue.name(), "duplicate-package-name");
+        assert_eq!(
+            issue.message(),
+            "package name '@acme/utils' is declared in 2 directories: /fake/@acme/utils, /fake/libs/utils"
         );
 
-        assert_eq!(fix.version_replacements.len(), 1);
-        assert_eq!(fix.version_replacements[0].catalog_ref, "catalog:legacy");
+        let options = LintOptions {
+            relative_to: Some(PathBuf::from("/fake/libs")),
+            ..Default::default()
+        };
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &options);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(
+            issue.message(),
+            "package name '@acme/utils' is declared in 2 directories: ../@acme/utils, utils"
+        );
     }
 
     #[test]
-    fn version_replacements_empty_when_rule_excluded() {
+    fn direct_version_marks_catalog_entry_as_used() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // Should report no-direct-version error but NOT unused-catalog-entry
+        assert_eq!(issues.errors_count(), 1);
+        assert_eq!(issues.warnings_count(), 0);
+
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "no-direct-version");
+    }
+
+    #[test]
+    fn direct_version_no_false_unused_when_rule_excluded() {
         let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
         let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
 
-        let (_issues, fix) = collect_issues(
+        let (issues, _fix, _usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::Exclude(vec!["no-direct-version".to_string()]),
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                rule_filter: Filter::Exclude(vec!["no-direct-version".to_string()]),
+                ..Default::default()
+            },
         );
 
-        assert!(fix.version_replacements.is_empty());
+        // With no-direct-version excluded, there should be zero issues —
+        // the catalog entry is still considered used
+        assert!(issues.is_empty());
+        assert_eq!(issues.suppressed_count(), 1);
     }
 
     #[test]
-    fn uncataloged_dependency_detected() {
-        let catalogs = make_catalogs(vec![]);
-        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+    fn reserved_entries_are_not_unused() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("@acme/new-ui", "^0.1.0")]);
+        let packages = vec![make_package("app", vec![])];
 
-        let (issues, _fix) = collect_issues(
+        let (issues, fix, _usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                reserved_entries: HashSet::from([CatalogEntry {
+                    catalog_name: None,
+                    dependency_name: "@acme/new-ui".to_string(),
+                }]),
+                ..Default::default()
+            },
         );
 
         assert_eq!(issues.warnings_count(), 1);
         let (_, issue) = issues.iter().next().unwrap();
-        assert_eq!(issue.name(), "no-uncataloged-dependency");
+        assert!(issue.message().contains("'react'"));
+        assert_eq!(fix.unused_entries.len(), 1);
+        assert_eq!(fix.unused_entries[0].dependency_name, "react");
     }
 
     #[test]
-    fn uncataloged_dependency_skipped_when_in_catalog() {
-        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
-        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+    fn ignore_unused_in_skips_only_unused_for_that_catalog() {
+        let mut catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        catalogs.insert_named(
+            "future".to_string(),
+            IndexMap::from([
+                ("vite".to_string(), "^6.0.0".to_string()),
+                ("vitest".to_string(), "*".to_string()),
+            ]),
+        );
+        let packages = vec![make_package("app", vec![])];
+        let options = LintOptions {
+            ignore_unused_in: vec!["future".to_string()],
+            permissive_specs: vec!["*".to_string()],
+            ..Default::default()
+        };
+
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &options);
+
+        let reported: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.dependency()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                ("unused-catalog-entry", Some("react")),
+                ("catalog-overly-permissive", Some("vitest")),
+            ]
+        );
+        assert_eq!(fix.unused_entries.len(), 1);
 
-        let (issues, _fix) = collect_issues(
+        let (issues, _, _) = collect_issues(
             &packages,
             &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                ignore_unused_in: vec!["default".to_string()],
+                ..Default::default()
+            },
         );
-
-        // Should only have no-direct-version, not no-uncataloged-dependency
-        assert!(issues.iter().all(|(_, i)| i.name() == "no-direct-version"));
+        let mut unused: Vec<Option<&str>> = issues.iter().map(|(_, i)| i.dependency()).collect();
+        unused.sort();
+        assert_eq!(unused, vec![Some("vite"), Some("vitest")]);
     }
 
     #[test]
-    fn uncataloged_dependency_skipped_for_special_protocols() {
-        let catalogs = make_catalogs(vec![]);
+    fn only_catalogs_scopes_catalog_rules_and_refs() {
+        let mut catalogs = make_catalogs(vec![("react", "*"), ("lodash", "^4.17.21")]);
+        catalogs.insert_named(
+            "react17".to_string(),
+            IndexMap::from([
+                ("react".to_string(), "*".to_string()),
+                ("react-dom".to_string(), "^17.0.2".to_string()),
+            ]),
+        );
+        catalogs.insert_named("legacy".to_string(), IndexMap::new());
         let packages = vec![make_package(
             "app",
-            vec![("my-lib", "workspace:*"), ("my-link", "link:../lib")],
+            vec![
+                ("react", "catalog:react17"),
+                ("vite", "catalog:"),
+                ("jest", "catalog:legacy"),
+                ("redux", "catalog:react17"),
+            ],
         )];
+        let options = LintOptions {
+            only_catalogs: vec!["react17".to_string()],
+            permissive_specs: vec!["*".to_string()],
+            ..Default::default()
+        };
 
-        let (issues, _fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &options);
+
+        let reported: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.dependency()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                ("catalog-entry-exists", Some("redux")),
+                ("unused-catalog-entry", Some("react-dom")),
+                ("catalog-overly-permissive", Some("react")),
+                // Involves react17, so it's reported even though the default catalog isn't selected
+                ("redundant-catalog-duplication", Some("react")),
+            ]
+        );
+        assert_eq!(
+            fix.unused_entries,
+            vec![CatalogEntry {
+                catalog_name: Some("react17".to_string()),
+                dependency_name: "react-dom".to_string(),
+            }]
         );
-
-        assert!(issues.is_empty());
     }
 
     #[test]
-    fn uncataloged_dependency_respects_rule_exclusion() {
-        let catalogs = make_catalogs(vec![]);
-        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
-
-        let (issues, _fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::Exclude(vec!["no-uncataloged-dependency".to_string()]),
-            &Filter::None,
-            &Filter::None,
+    fn catalog_and_direct_refs_mark_default_and_named_entries_used() {
+        let mut catalogs = make_catalogs(vec![
+            ("react", "^18.2.0"),
+            ("lodash", "^4.17.21"),
+            ("vite", "^5.0.0"),
+        ]);
+        catalogs.insert_named(
+            "legacy".to_string(),
+            IndexMap::from([
+                ("react".to_string(), "^16.14.0".to_string()),
+                ("jquery".to_string(), "^3.7.0".to_string()),
+                ("moment".to_string(), "^2.30.0".to_string()),
+            ]),
         );
+        let packages = vec![make_package(
+            "app",
+            vec![
+                ("react", "catalog:legacy"),
+                ("lodash", "catalog:"),
+                ("jquery", "^3.7.0"),
+            ],
+        )];
 
-        assert!(issues.is_empty());
+        let (_issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let mut unused: Vec<(Option<&str>, &str)> = fix
+            .unused_entries
+            .iter()
+            .map(|e| (e.catalog_name.as_deref(), e.dependency_name.as_str()))
+            .collect();
+        unused.sort();
+        assert_eq!(
+            unused,
+            vec![(None, "react"), (None, "vite"), (Some("legacy"), "moment")]
+        );
     }
 
     #[test]
-    fn uncataloged_dependency_respects_dependency_filter() {
-        let catalogs = make_catalogs(vec![]);
-        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+    fn returns_unused_entries() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![make_package("app", vec![("react", "catalog:")])];
 
-        let (issues, _fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::Exclude(vec!["lodash".to_string()]),
-        );
+        let (_issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
 
-        assert!(issues.is_empty());
+        assert_eq!(fix.unused_entries.len(), 1);
+        assert_eq!(fix.unused_entries[0].dependency_name, "lodash");
+        assert_eq!(fix.unused_entries[0].catalog_name, None);
     }
 
     #[test]
-    fn uncataloged_fix_collects_catalog_additions() {
-        let catalogs = make_catalogs(vec![]);
-        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
-
-        let (_issues, fix) = collect_issues(
-            &packages,
-            &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+    fn unknown_packages_reports_names_not_in_workspace() {
+        let packages = vec![
+            make_package("@acme/app", vec![]),
+            make_package("@acme/ui", vec![]),
+        ];
+        let requested = vec![
+            "@acme/app".to_string(),
+            "@acme/ap".to_string(),
+            "(root)".to_string(),
+        ];
+        assert_eq!(
+            unknown_packages(&packages, &requested),
+            vec!["@acme/ap", "(root)"]
         );
-
-        assert_eq!(fix.catalog_additions.len(), 1);
-        assert_eq!(fix.catalog_additions[0].dependency_name, "lodash");
-        assert_eq!(fix.catalog_additions[0].version, "^4.17.21");
-        assert_eq!(fix.catalog_addition_replacements.len(), 1);
-        assert_eq!(fix.catalog_addition_replacements[0].catalog_ref, "catalog:");
     }
 
     #[test]
-    fn uncataloged_fix_deduplicates_same_version() {
-        let catalogs = make_catalogs(vec![]);
+    fn excluded_package_deps_still_mark_catalog_entries_as_used() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
         let packages = vec![
-            make_package("app-a", vec![("lodash", "^4.17.21")]),
-            make_package("app-b", vec![("lodash", "^4.17.21")]),
+            make_package("app", vec![("react", "catalog:")]),
+            make_package("excluded-pkg", vec![("lodash", "catalog:")]),
         ];
 
-        let (_issues, fix) = collect_issues(
+        let (issues, fix, _usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                package_filter: Filter::Exclude(vec!["excluded-pkg".to_string()]),
+                ..Default::default()
+            },
         );
 
-        // 1 catalog addition, 2 version replacements
-        assert_eq!(fix.catalog_additions.len(), 1);
-        assert_eq!(fix.catalog_addition_replacements.len(), 2);
+        // lodash should NOT be reported as unused — excluded-pkg references it
+        assert!(fix.unused_entries.is_empty());
+        // No issues from excluded-pkg should be reported
+        assert_eq!(issues.errors_count(), 0);
+        assert_eq!(issues.warnings_count(), 0);
+        assert_eq!(issues.skipped_packages(), 1);
     }
 
     #[test]
-    fn uncataloged_fix_skips_conflicting_versions() {
-        let catalogs = make_catalogs(vec![]);
+    fn unchanged_packages_are_skipped_but_count_as_uses() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
         let packages = vec![
-            make_package("app-a", vec![("lodash", "^4.17.21")]),
-            make_package("app-b", vec![("lodash", "^4.17.20")]),
+            make_package("new-app", vec![("react", "^18.2.0")]),
+            make_package("old-app", vec![("lodash", "^4.17.21")]),
         ];
 
-        let (_issues, fix) = collect_issues(
+        let (issues, fix, _usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::None,
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                unchanged_packages: HashSet::from([PathBuf::from("/fake/old-app")]),
+                ..Default::default()
+            },
         );
 
-        // Conflicting versions — skip fix entirely for this dep
-        assert!(fix.catalog_additions.is_empty());
-        assert!(fix.catalog_addition_replacements.is_empty());
+        let reported: Vec<String> = issues.iter().map(|(pkg, _)| pkg.to_string()).collect();
+        assert_eq!(reported, vec!["new-app"]);
+        assert!(fix.unused_entries.is_empty());
+        assert_eq!(issues.skipped_packages(), 1);
     }
 
     #[test]
-    fn uncataloged_fix_empty_when_rule_excluded() {
-        let catalogs = make_catalogs(vec![]);
-        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+    fn packages_outside_only_filter_still_mark_catalog_entries_as_used() {
+        let mut catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let mut legacy = IndexMap::new();
+        legacy.insert("jquery".to_string(), "^3.6.0".to_string());
+        legacy.insert("moment".to_string(), "^2.29.0".to_string());
+        catalogs.insert_named("legacy".to_string(), legacy);
+        let packages = vec![
+            make_package("app", vec![("react", "catalog:")]),
+            make_package(
+                "old-app",
+                vec![
+                    ("lodash", "^4.17.21"),
+                    ("jquery", "catalog:legacy"),
+                    ("moment", "Catalog:legacy"),
+                ],
+            ),
+        ];
 
-        let (_issues, fix) = collect_issues(
+        let (issues, fix, _usage) = collect_issues(
             &packages,
             &catalogs,
-            Filter::Exclude(vec!["no-uncataloged-dependency".to_string()]),
-            &Filter::None,
-            &Filter::None,
+            &LintOptions {
+                package_filter: Filter::Only(vec!["app".to_string()]),
+                ..Default::default()
+            },
         );
 
-        assert!(fix.catalog_additions.is_empty());
-        assert!(fix.catalog_addition_replacements.is_empty());
+        assert!(fix.unused_entries.is_empty());
+        assert!(issues.is_empty());
+        assert_eq!(issues.skipped_packages(), 1);
+    }
+
+    #[test]
+    fn excluded_dependency_still_marks_catalog_entry_as_used() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "catalog:"), ("lodash", "catalog:")],
+        )];
+
+        let (issues, fix, usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                dependency_filter: Filter::Exclude(vec!["lodash".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(fix.unused_entries.is_empty());
+        assert!(issues.is_empty());
+        assert_eq!(issues.skipped_dependencies(), 1);
+        let lodash = usage.get(&CatalogEntry {
+            catalog_name: None,
+            dependency_name: "lodash".to_string(),
+        });
+        assert_eq!(lodash.catalog_packages, 1);
+    }
+
+    #[test]
+    fn unused_entries_empty_when_rule_excluded() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![make_package("app", vec![("react", "catalog:")])];
+
+        let (issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Exclude(vec!["unused-catalog-entry".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(fix.unused_entries.is_empty());
+        // Unused entries aren't even looked for, so none count as suppressed
+        assert_eq!(issues.suppressed_count(), 0);
+    }
+
+    #[test]
+    fn only_runs_specified_rules() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (issues, _fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Only(vec!["no-direct-version".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        // Only no-direct-version should be reported, unused-catalog-entry should be filtered
+        assert_eq!(issues.errors_count(), 1);
+        assert_eq!(issues.warnings_count(), 0);
+
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "no-direct-version");
+    }
+
+    #[test]
+    fn only_excludes_unspecified_rules() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Only(vec!["unused-catalog-entry".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        // no-direct-version should be filtered out, only unused-catalog-entry should remain
+        assert_eq!(issues.errors_count(), 0);
+        assert_eq!(issues.warnings_count(), 1);
+        assert_eq!(fix.unused_entries.len(), 1);
+    }
+
+    #[test]
+    fn collects_version_replacements_for_direct_versions() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (_issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(fix.version_replacements.len(), 1);
+        assert_eq!(fix.version_replacements[0].dependency_name, "react");
+        assert_eq!(fix.version_replacements[0].catalog_ref, "catalog:");
+    }
+
+    #[test]
+    fn version_replacements_skip_dependencies_in_several_catalogs() {
+        let mut named = IndexMap::new();
+        let mut legacy = IndexMap::new();
+        legacy.insert("react".to_string(), "^16.0.0".to_string());
+        named.insert("legacy".to_string(), legacy);
+
+        let catalogs = WorkspaceCatalogs::new(
+            {
+                let mut m = IndexMap::new();
+                m.insert("react".to_string(), "^18.2.0".to_string());
+                m
+            },
+            named,
+        );
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // Both catalog: and catalog:legacy would do; --fix doesn't guess
+        assert!(fix.version_replacements.is_empty());
+        assert_eq!(issues.len(), 1);
+        assert!(issues.iter().all(|(_, issue)| !issue.fixable()));
+    }
+
+    #[test]
+    fn remove_fixed_keeps_issues_fix_left_alone() {
+        let mut named = IndexMap::new();
+        let mut legacy = IndexMap::new();
+        legacy.insert("react".to_string(), "^16.0.0".to_string());
+        named.insert("legacy".to_string(), legacy);
+        let mut default = IndexMap::new();
+        default.insert("react".to_string(), "^18.2.0".to_string());
+        default.insert("vue".to_string(), "^3.4.0".to_string());
+        let catalogs = WorkspaceCatalogs::new(default, named);
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "^18.2.0"), ("vue", "^3.4.0")],
+        )];
+
+        let (mut issues, fix, _usage) =
+            collect_issues(&packages, &catalogs, &LintOptions::default());
+        assert_eq!(fix.version_replacements.len(), 1);
+        issues.remove_fixed("no-direct-version");
+
+        assert_eq!(issues.len(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.dependency(), Some("react"));
+    }
+
+    #[test]
+    fn missing_named_entries_roll_up_into_one_suggestion_per_catalog() {
+        let mut named = IndexMap::new();
+        let mut react17 = IndexMap::new();
+        react17.insert("react".to_string(), "^17.0.2".to_string());
+        named.insert("react17".to_string(), react17);
+        let catalogs = WorkspaceCatalogs::new(IndexMap::new(), named);
+        let packages = vec![
+            make_package(
+                "web",
+                vec![
+                    ("react", "catalog:react17"),
+                    ("react-dom", "catalog:react17"),
+                ],
+            ),
+            make_package(
+                "docs",
+                vec![
+                    ("react-dom", "catalog:react17"),
+                    ("scheduler", "catalog:react17"),
+                    ("vue", "catalog:vue2"),
+                ],
+            ),
+        ];
+
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let incomplete: Vec<(&PackageType, String)> = issues
+            .iter()
+            .filter(|(_, issue)| issue.name() == "incomplete-catalog")
+            .map(|(pkg, issue)| (pkg, issue.message()))
+            .collect();
+        assert_eq!(
+            incomplete,
+            vec![(
+                &PackageType::Catalog,
+                "2 packages reference 'react-dom', 'scheduler' as \"catalog:react17\", \
+                 but catalog \"react17\" doesn't define them. Add them to the catalog."
+                    .to_string()
+            )]
+        );
+        // The per-package errors are still reported
+        assert_eq!(issues.errors_count(), 4);
+
+        // The roll-up doesn't depend on catalog-entry-exists being reported
+        let options = LintOptions {
+            rule_filter: Filter::Only(vec!["incomplete-catalog".to_string()]),
+            ..LintOptions::default()
+        };
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &options);
+        let reported: Vec<&str> = issues.iter().map(|(_, issue)| issue.name()).collect();
+        assert_eq!(reported, ["incomplete-catalog"]);
+    }
+
+    #[test]
+    fn version_replacements_use_named_catalog_when_no_default() {
+        let mut named = IndexMap::new();
+        let mut legacy = IndexMap::new();
+        legacy.insert("react".to_string(), "^16.0.0".to_string());
+        named.insert("legacy".to_string(), legacy);
+
+        let catalogs = WorkspaceCatalogs::new(IndexMap::new(), named);
+        let packages = vec![make_package("app", vec![("react", "^16.0.0")])];
+
+        let (_issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(fix.version_replacements.len(), 1);
+        assert_eq!(fix.version_replacements[0].catalog_ref, "catalog:legacy");
+    }
+
+    #[test]
+    fn version_replacements_empty_when_rule_excluded() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (_issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Exclude(vec!["no-direct-version".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(fix.version_replacements.is_empty());
+    }
+
+    #[test]
+    fn uncataloged_dependency_detected() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(issues.warnings_count(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "no-uncataloged-dependency");
+    }
+
+    #[test]
+    fn uncataloged_dependency_skipped_when_in_catalog() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package("app", vec![("react", "^18.2.0")])];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // Should only have no-direct-version, not no-uncataloged-dependency
+        assert!(issues.iter().all(|(_, i)| i.name() == "no-direct-version"));
+    }
+
+    #[test]
+    fn uncataloged_dependency_skipped_for_special_protocols() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package(
+                "app",
+                vec![("my-lib", "workspace:*"), ("my-link", "link:../lib")],
+            ),
+            make_package("my-lib", vec![]),
+        ];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn jsr_specifier_is_not_a_direct_version() {
+        let catalogs = make_catalogs(vec![("@std/path", "jsr:^1.0.0")]);
+        let packages = vec![make_package(
+            "app",
+            vec![("@std/path", "jsr:@std/path@^1.0.0")],
+        )];
+
+        let options = LintOptions {
+            rule_filter: Filter::Exclude(vec!["unused-catalog-entry".to_string()]),
+            ..Default::default()
+        };
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &options);
+
+        assert!(issues.is_empty());
+        assert!(fix.version_replacements.is_empty());
+    }
+
+    #[test]
+    fn uncataloged_dependency_respects_rule_exclusion() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+
+        let (issues, _fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Exclude(vec!["no-uncataloged-dependency".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn uncataloged_dependency_respects_dependency_filter() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+
+        let (issues, _fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                dependency_filter: Filter::Exclude(vec!["lodash".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(issues.is_empty());
+        assert_eq!(issues.skipped_dependencies(), 1);
+    }
+
+    #[test]
+    fn uncataloged_fix_collects_catalog_additions() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+
+        let (_issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(fix.catalog_additions.len(), 1);
+        assert_eq!(fix.catalog_additions[0].dependency_name, "lodash");
+        assert_eq!(fix.catalog_additions[0].version, "^4.17.21");
+        assert_eq!(fix.catalog_addition_replacements.len(), 1);
+        assert_eq!(fix.catalog_addition_replacements[0].catalog_ref, "catalog:");
+    }
+
+    #[test]
+    fn uncataloged_fix_deduplicates_same_version() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package("app-a", vec![("lodash", "^4.17.21")]),
+            make_package("app-b", vec![("lodash", "^4.17.21")]),
+        ];
+
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // 1 catalog addition, 2 version replacements
+        assert_eq!(fix.catalog_additions.len(), 1);
+        assert_eq!(fix.catalog_addition_replacements.len(), 2);
+        assert!(issues.iter().all(|(_, issue)| issue.fixable()));
+    }
+
+    #[test]
+    fn uncataloged_fix_skips_conflicting_versions() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package("app-a", vec![("lodash", "^4.17.21")]),
+            make_package("app-b", vec![("lodash", "^4.17.20")]),
+        ];
+
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // Conflicting versions — skip fix entirely for this dep
+        assert!(fix.catalog_additions.is_empty());
+        assert!(fix.catalog_addition_replacements.is_empty());
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|(_, issue)| !issue.fixable()));
+    }
+
+    #[test]
+    fn uncataloged_versions_in_excluded_packages_do_not_conflict() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package("app-a", vec![("lodash", "^4.17.21")]),
+            make_package("app-b", vec![("lodash", "^4.17.20")]),
+        ];
+
+        let (issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                package_filter: Filter::Exclude(vec!["app-b".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(fix.catalog_additions.len(), 1);
+        assert_eq!(issues.len(), 1);
+        assert!(issues.iter().all(|(_, issue)| issue.fixable()));
+    }
+
+    #[test]
+    fn uncataloged_fix_empty_when_rule_excluded() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![make_package("app", vec![("lodash", "^4.17.21")])];
+
+        let (_issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Exclude(vec!["no-uncataloged-dependency".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        assert!(fix.catalog_additions.is_empty());
+        assert!(fix.catalog_addition_replacements.is_empty());
+    }
+
+    #[test]
+    fn shared_dependency_over_threshold_must_use_catalog() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![
+            make_package("a", vec![("react", "catalog:"), ("lodash", "^4.17.21")]),
+            make_package("b", vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]),
+            make_package("c", vec![("react", "catalog:"), ("lodash", "^4.17.20")]),
+            make_package("d", vec![("vite", "^5.0.0")]),
+        ];
+
+        let options = LintOptions {
+            rule_filter: Filter::Only(vec!["shared-dependency-uses-catalog".to_string()]),
+            catalog_threshold: Some(3),
+            ..Default::default()
+        };
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &options);
+
+        let messages: Vec<String> = issues
+            .iter()
+            .map(|(pkg, issue)| format!("{pkg}: {}", issue.message()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "a: 'lodash' is used by 3 packages (threshold 3) but \"^4.17.21\" in dependencies is not in any catalog",
+                "b: 'react' is used by 3 packages (threshold 3) but dependencies uses \"^18.2.0\" instead of a catalog: reference",
+                "b: 'lodash' is used by 3 packages (threshold 3) but \"^4.17.21\" in dependencies is not in any catalog",
+                "c: 'lodash' is used by 3 packages (threshold 3) but \"^4.17.20\" in dependencies is not in any catalog",
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_dependency_rule_off_without_threshold() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package("a", vec![("lodash", "^4.17.21")]),
+            make_package("b", vec![("lodash", "^4.17.21")]),
+        ];
+
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert!(
+            issues
+                .iter()
+                .all(|(_, issue)| issue.name() != "shared-dependency-uses-catalog")
+        );
+    }
+
+    #[test]
+    fn overly_permissive_catalog_versions_detected() {
+        let catalogs = make_catalogs(vec![
+            ("react", "*"),
+            ("lodash", "^4.17.21"),
+            ("vite", " latest "),
+        ]);
+        let options = LintOptions {
+            rule_filter: Filter::Only(vec!["catalog-overly-permissive".to_string()]),
+            permissive_specs: vec!["*".to_string(), "latest".to_string()],
+            ..Default::default()
+        };
+
+        let (issues, _, _) = collect_issues(&[], &catalogs, &options);
+
+        let flagged: Vec<Option<&str>> = issues.iter().map(|(_, i)| i.dependency()).collect();
+        assert_eq!(flagged, vec![Some("react"), Some("vite")]);
+    }
+
+    #[test]
+    fn prerelease_catalog_versions_detected_unless_allowed() {
+        let catalogs = make_catalogs(vec![
+            ("foo", "2.0.0-beta.3"),
+            ("bar", "1.0.0+build.5"),
+            ("react", "^18.2.0"),
+            ("typescript", "^5.5.0-rc.1"),
+            ("vite-next", "npm:vite@>=6.0.0-alpha.1 <7"),
+        ]);
+        let options = LintOptions {
+            rule_filter: Filter::Only(vec!["catalog-prerelease-version".to_string()]),
+            allow_prerelease: vec!["typescript".to_string()],
+            ..Default::default()
+        };
+
+        let (issues, _, _) = collect_issues(&[], &catalogs, &options);
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'foo' in the default catalog uses \"2.0.0-beta.3\", a pre-release version",
+                "'bar' in the default catalog uses \"1.0.0+build.5\", a version with build metadata",
+                "'vite-next' in the default catalog uses \"npm:vite@>=6.0.0-alpha.1 <7\", a pre-release version",
+            ]
+        );
+    }
+
+    #[test]
+    fn git_and_tarball_catalog_entries_skip_range_rules() {
+        let catalogs = make_catalogs(vec![
+            (
+                "ui",
+                "git+https://github.com/acme/ui.git#semver:1.x || 2.0.0-rc.1",
+            ),
+            ("tokens", "https://cdn.acme.dev/tokens-1.0.0-beta.2.tgz"),
+            ("foo", "2.0.0-beta.3"),
+        ]);
+        let packages = vec![make_package(
+            "app",
+            vec![
+                ("ui", "catalog:"),
+                ("tokens", "catalog:"),
+                ("foo", "catalog:"),
+            ],
+        )];
+
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.dependency()))
+            .collect();
+        assert_eq!(reported, vec![("catalog-prerelease-version", Some("foo"))]);
+    }
+
+    #[test]
+    fn aliased_catalog_entries_are_matched_by_key_and_checked_by_target() {
+        let catalogs = make_catalogs(vec![
+            ("react-18", "npm:react@^18.2.0"),
+            ("lodash-any", "npm:lodash@*"),
+            ("vite-latest", "npm:vite"),
+        ]);
+        let packages = vec![make_package(
+            "app",
+            vec![
+                ("react-18", "catalog:"),
+                ("lodash-any", "catalog:"),
+                ("vite-latest", "catalog:"),
+            ],
+        )];
+        let options = LintOptions {
+            permissive_specs: vec!["*".to_string(), String::new()],
+            ..Default::default()
+        };
+
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &options);
+
+        let reported: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.dependency()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                ("catalog-overly-permissive", Some("lodash-any")),
+                ("catalog-overly-permissive", Some("vite-latest")),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_named_catalog_reported_and_explained() {
+        let mut catalogs = make_catalogs(vec![]);
+        catalogs.insert_named("react16".to_string(), IndexMap::new());
+        let packages = vec![make_package("app", vec![("react", "catalog:react16")])];
+
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let messages: Vec<(&str, String)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.message()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    "catalog-entry-exists",
+                    "'react' references \"catalog:react16\" in dependencies but catalog \"react16\" is empty".to_string()
+                ),
+                (
+                    "empty-catalog",
+                    "catalog \"react16\" is declared but has no entries".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn optional_peer_catalog_refs_are_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{
+  "name": "ui",
+  "peerDependencies": { "react": "catalog:", "vue": "catalog:" },
+  "peerDependenciesMeta": { "vue": { "optional": true } }
+}"#,
+        )
+        .unwrap();
+        let packages = vec![Package::load(dir.path(), false).unwrap()];
+        assert_eq!(packages[0].inner.peer_dependencies_meta.len(), 1);
+
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let reported: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.dependency()))
+            .collect();
+        assert_eq!(reported, vec![("catalog-entry-exists", Some("vue"))]);
+    }
+
+    #[test]
+    fn peer_catalog_consistency_flags_non_overlapping_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{
+  "name": "ui",
+  "peerDependencies": { "react": "catalog:", "vue": "catalog:" },
+  "devDependencies": { "react": "^17.0.2", "vue": "catalog:" }
+}"#,
+        )
+        .unwrap();
+        let packages = vec![Package::load(dir.path(), false).unwrap()];
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("vue", "^3.4.0")]);
+        let options = LintOptions {
+            rule_filter: Filter::Only(vec!["peer-catalog-consistency".to_string()]),
+            peer_consistency: true,
+            ..Default::default()
+        };
+
+        let (issues, _, _) = collect_issues(&packages, &catalogs, &options);
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react' in peerDependencies resolves \"catalog:\" to \"^18.2.0\", which doesn't overlap \"^17.0.2\" in devDependencies"
+            ]
+        );
+
+        let (issues, _, _) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                peer_consistency: false,
+                ..options
+            },
+        );
+        assert_eq!(issues.len(), 0);
+    }
+
+    #[test]
+    fn conflicting_dependency_kinds_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{
+  "name": "app",
+  "dependencies": { "react": "^18.0.0", "lodash": "catalog:", "vite": "catalog:" },
+  "devDependencies": { "react": "^17.0.0", "lodash": "catalog:", "vite": "catalog:build" },
+  "peerDependencies": { "lodash": "^4.0.0" }
+}"#,
+        )
+        .unwrap();
+        let packages = vec![Package::load(dir.path(), false).unwrap()];
+        let options = LintOptions {
+            rule_filter: Filter::Only(vec!["conflicting-dependency-kinds".to_string()]),
+            ..Default::default()
+        };
+
+        let (issues, _, _) = collect_issues(&packages, &make_catalogs(vec![]), &options);
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react' is declared with different versions: \"^18.0.0\" in dependencies, \"^17.0.0\" in devDependencies",
+                "'vite' is declared with different versions: \"catalog:\" in dependencies, \"catalog:build\" in devDependencies",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_dependency_kind_flows_through_rules_and_fixes() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        for kind in [
+            DependencyKind::Dependencies,
+            DependencyKind::DevDependencies,
+            DependencyKind::PeerDependencies,
+            DependencyKind::OptionalDependencies,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(
+                dir.path().join("package.json"),
+                format!(
+                    r#"{{
+  "name": "app",
+  "{kind}": {{
+    "react": "^18.2.0",
+    "lodash": "Catalog:",
+    "vite": "^5.0.0",
+    "vue": "catalog:"
+  }}
+}}"#
+                ),
+            )
+            .unwrap();
+            let packages = vec![Package::load(dir.path(), false).unwrap()];
+
+            let (issues, fix, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+            let reported: Vec<&str> = issues.iter().map(|(_, i)| i.name()).collect();
+            assert_eq!(
+                reported,
+                vec![
+                    "no-direct-version",
+                    "malformed-catalog-ref",
+                    "no-uncataloged-dependency",
+                    "catalog-entry-exists",
+                ],
+                "{kind}"
+            );
+            for (_, issue) in issues.iter() {
+                assert!(
+                    issue.message().contains(&format!(" in {kind} ")),
+                    "{}",
+                    issue.message()
+                );
+            }
+            assert_eq!(fix.version_replacements.len(), 1);
+            assert_eq!(fix.version_replacements[0].kind, kind);
+            assert_eq!(fix.catalog_addition_replacements[0].kind, kind);
+        }
+    }
+
+    #[test]
+    fn scoped_and_dotted_names_resolve_and_mark_entries_used() {
+        let yaml = r#"
+catalog:
+  "@types/node": "^20.11.0"
+  "@babel/core": "^7.24.0"
+  "lodash.merge": "^4.6.2"
+  "@acme/unused": "^1.0.0"
+catalogs:
+  "@acme/design":
+    "@acme/ui": "^2.0.0"
+"#;
+        let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
+        let packages = vec![make_package(
+            "app",
+            vec![
+                ("@types/node", "catalog:"),
+                ("lodash.merge", "catalog:default"),
+                ("@acme/ui", "catalog:@acme/design"),
+                ("@babel/core", "^7.24.0"),
+                ("@acme/missing", "catalog:@acme/design"),
+            ],
+        )];
+
+        let (issues, fix, _) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'@babel/core' uses \"^7.24.0\" in dependencies but is available in catalog: default. Use \"catalog:\" instead.",
+                "'@acme/missing' references \"catalog:@acme/design\" in dependencies but is not defined in catalog \"@acme/design\"",
+                "'@acme/unused' (\"^1.0.0\") in the default catalog is never referenced",
+            ]
+        );
+        assert_eq!(fix.version_replacements[0].dependency_name, "@babel/core");
+        assert_eq!(fix.version_replacements[0].catalog_ref, "catalog:");
+        let unused: Vec<&str> = fix
+            .unused_entries
+            .iter()
+            .map(|e| e.dependency_name.as_str())
+            .collect();
+        assert_eq!(unused, vec!["@acme/unused"]);
+    }
+
+    #[test]
+    fn policy_kinds_select_peer_and_optional_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{
+  "name": "app",
+  "dependencies": { "@acme/ui": "catalog:" },
+  "devDependencies": { "@acme/lint": "catalog:" },
+  "peerDependencies": { "@acme/core": "catalog:" },
+  "optionalDependencies": { "@acme/native": "catalog:" }
+}"#,
+        )
+        .unwrap();
+        let packages = vec![Package::load(dir.path(), false).unwrap()];
+        let policy: Policy = toml::from_str(
+            r#"
+name = "acme-internal"
+dependency = "@acme/*"
+catalog = "internal"
+kinds = ["peerDependencies", "optionalDependencies"]
+"#,
+        )
+        .unwrap();
+
+        let (issues, _, _) = collect_issues(
+            &packages,
+            &make_catalogs(vec![]),
+            &LintOptions {
+                rule_filter: Filter::Only(vec!["acme-internal".to_string()]),
+                policies: vec![policy],
+                ..Default::default()
+            },
+        );
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'@acme/core' uses \"catalog:\" in peerDependencies but policy requires \"catalog:internal\"",
+                "'@acme/native' uses \"catalog:\" in optionalDependencies but policy requires \"catalog:internal\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn usage_separates_catalog_and_direct_refs() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let packages = vec![
+            make_package("app-a", vec![("react", "catalog:"), ("lodash", "^4.17.21")]),
+            make_package("app-b", vec![("react", "^18.2.0")]),
+        ];
+
+        let (_issues, _fix, usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let react = usage.get(&CatalogEntry {
+            catalog_name: None,
+            dependency_name: "react".to_string(),
+        });
+        assert_eq!(react.catalog_packages, 1);
+        assert_eq!(react.direct_packages, 1);
+
+        let lodash = usage.get(&CatalogEntry {
+            catalog_name: None,
+            dependency_name: "lodash".to_string(),
+        });
+        assert_eq!(lodash.catalog_packages, 0);
+        assert_eq!(lodash.direct_packages, 1);
+    }
+
+    #[test]
+    fn miscased_catalog_ref_reported_as_malformed() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let packages = vec![make_package("app", vec![("react", "Catalog:")])];
+
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // Only malformed-catalog-ref — not no-direct-version, and the entry is not unused
+        assert_eq!(issues.errors_count(), 1);
+        assert_eq!(issues.warnings_count(), 0);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "malformed-catalog-ref");
+        assert!(issue.message().contains("Use \"catalog:\" instead"));
+        assert!(fix.version_replacements.is_empty());
+        assert!(fix.unused_entries.is_empty());
+    }
+
+    #[test]
+    fn uppercase_named_catalog_ref_suggests_lowercase_protocol() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![make_package("app", vec![("react", "CATALOG:react16")])];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(issues.errors_count(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "malformed-catalog-ref");
+        assert!(issue.message().contains("Use \"catalog:react16\" instead"));
+    }
+
+    #[test]
+    fn padded_and_slashed_catalog_refs_reported_as_malformed() {
+        let mut catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        catalogs.insert_named(
+            "react16".to_string(),
+            IndexMap::from([("react-dom".to_string(), "^16.14.0".to_string())]),
+        );
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "catalog: "), ("react-dom", "catalog:/react16")],
+        )];
+
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        // No catalog-entry-exists for a " " or "/react16" catalog, and both entries count as used
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react' uses \"catalog: \" in dependencies but the catalog name can't be surrounded by whitespace or slashes. Use \"catalog:\" instead.",
+                "'react-dom' uses \"catalog:/react16\" in dependencies but the catalog name can't be surrounded by whitespace or slashes. Use \"catalog:react16\" instead.",
+            ]
+        );
+        assert!(fix.unused_entries.is_empty());
+    }
+
+    #[test]
+    fn verify_catalog_refs_reports_broken_refs_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pnpm-workspace.yaml"),
+            "packages:\n  - \"packages/*\"\n\ncatalog:\n  react: \"^18.2.0\"\n",
+        )
+        .unwrap();
+        let app = dir.path().join("packages/app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(
+            app.join("package.json"),
+            r#"{ "name": "app", "dependencies": { "react": "catalog:", "lodash": "catalog:" } }"#,
+        )
+        .unwrap();
+
+        let issues =
+            verify_catalog_refs(dir.path(), &LintOptions::default(), &LoadOptions::default())
+                .unwrap();
+
+        assert_eq!(issues.errors_count(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "catalog-entry-exists");
+        assert!(issue.message().contains("'lodash'"));
+    }
+
+    #[test]
+    fn renamed_workspace_file_lints_another_package_tree() {
+        let catalog_dir = tempfile::tempdir().unwrap();
+        let workspace_file = catalog_dir.path().join("workspace.test.yaml");
+        std::fs::write(
+            &workspace_file,
+            "packages:\n  - \"apps/*\"\n\ncatalog:\n  react: \"^18.2.0\"\n",
+        )
+        .unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let web = root.path().join("apps/web");
+        std::fs::create_dir_all(&web).unwrap();
+        std::fs::write(
+            web.join("package.json"),
+            r#"{ "name": "web", "dependencies": { "react": "catalog:", "lodash": "catalog:" } }"#,
+        )
+        .unwrap();
+
+        let (workspace, catalogs) = parse_workspace_file(&workspace_file).unwrap();
+        let (packages, _) =
+            collect_packages(root.path(), &workspace, &LoadOptions::default()).unwrap();
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(
+            packages[0].package_type,
+            PackageType::Workspace("web".to_string())
+        );
+        assert_eq!(issues.errors_count(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "catalog-entry-exists");
+        assert!(issue.message().contains("'lodash'"));
+    }
+
+    #[test]
+    fn duplicate_catalog_key_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace_file = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(
+            &workspace_file,
+            "catalog:\n  react: ^18.2.0\n  react: ^18.3.1\n",
+        )
+        .unwrap();
+
+        let (_, catalogs) = parse_workspace_file(&workspace_file).unwrap();
+        let packages = vec![make_package("app", vec![("react", "catalog:")])];
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(issues.len(), 1);
+        let (pkg, issue) = issues.iter().next().unwrap();
+        assert_eq!(*pkg, PackageType::Catalog);
+        assert_eq!(issue.name(), "duplicate-catalog-key");
+        assert_eq!(
+            issue.message(),
+            "'react' is listed more than once in the default catalog; only the last entry (\"^18.3.1\") is used"
+        );
+    }
+
+    #[test]
+    fn any_version_of_a_list_entry_matches_a_direct_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace_file = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(
+            &workspace_file,
+            "catalog:\n  react: [^18.3.1, ^18.2.0]\n  lodash: [^4.17.21, ^4.17.0]\n",
+        )
+        .unwrap();
+
+        let (_, catalogs) = parse_workspace_file(&workspace_file).unwrap();
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "^18.2.0"), ("vite", "^5.0.0")],
+        )];
+        let (issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                allow_matching_direct: true,
+                ..Default::default()
+            },
+        );
+
+        let messages: Vec<(&str, String)> = issues
+            .iter()
+            .map(|(_, issue)| (issue.name(), issue.message()))
+            .collect();
+        assert!(
+            !messages
+                .iter()
+                .any(|(name, _)| *name == "no-direct-version")
+        );
+        assert!(
+            messages.contains(&(
+                "unused-catalog-entry",
+                "'lodash' (\"^4.17.21, ^4.17.0\") in the default catalog is never referenced"
+                    .to_string()
+            ))
+        );
+        assert!(fix.version_replacements.is_empty());
+    }
+
+    #[test]
+    fn collect_packages_records_matching_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{ "name": "root" }"#).unwrap();
+        for (pkg, name) in [("apps/web", "web"), ("packages/ui", "ui")] {
+            std::fs::create_dir_all(dir.path().join(pkg)).unwrap();
+            std::fs::write(
+                dir.path().join(pkg).join("package.json"),
+                format!(r#"{{ "name": "{name}" }}"#),
+            )
+            .unwrap();
+        }
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("packages:\n  - \"apps/*\"\n  - \"packages/**\"\n").unwrap();
+
+        let (packages, _) =
+            collect_packages(dir.path(), &workspace, &LoadOptions::default()).unwrap();
+
+        let patterns: Vec<(String, Option<&str>)> = packages
+            .iter()
+            .map(|pkg| (pkg.package_type.to_string(), pkg.pattern.as_deref()))
+            .collect();
+        assert_eq!(
+            patterns,
+            vec![
+                ("(root)".to_string(), None),
+                ("web".to_string(), Some("apps/*")),
+                ("ui".to_string(), Some("packages/**")),
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_packages_keeps_order_across_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{ "name": "root" }"#).unwrap();
+        for i in 0..5 {
+            let pkg = dir.path().join(format!("packages/pkg-{i}"));
+            std::fs::create_dir_all(&pkg).unwrap();
+            std::fs::write(
+                pkg.join("package.json"),
+                format!(r#"{{ "name": "pkg-{i}" }}"#),
+            )
+            .unwrap();
+        }
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("packages:\n  - \"packages/*\"\n").unwrap();
+
+        let names = |threads| -> Vec<PackageType> {
+            collect_packages(
+                dir.path(),
+                &workspace,
+                &LoadOptions {
+                    threads,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|pkg| pkg.package_type)
+            .collect()
+        };
+
+        let sequential = names(1);
+        assert_eq!(sequential.len(), 6);
+        assert_eq!(sequential[0], PackageType::Root);
+        assert_eq!(names(2), sequential);
+        assert_eq!(names(0), sequential);
+    }
+
+    #[test]
+    fn collect_packages_skips_ignored_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["app", "legacy-a", "legacy-b"] {
+            let pkg = dir.path().join("packages").join(name);
+            std::fs::create_dir_all(&pkg).unwrap();
+            std::fs::write(
+                pkg.join("package.json"),
+                format!(r#"{{ "name": "{name}" }}"#),
+            )
+            .unwrap();
+        }
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("packages:\n  - \"packages/*\"\n").unwrap();
+
+        let (packages, _) = collect_packages(
+            dir.path(),
+            &workspace,
+            &LoadOptions {
+                ignored_paths: vec![glob::Pattern::new("packages/legacy-*").unwrap()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(packages.len(), 1);
+        assert_eq!(
+            packages[0].package_type,
+            PackageType::Workspace("app".to_string())
+        );
+    }
+
+    #[test]
+    fn package_names_under_packages_are_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("packages/app")).unwrap();
+        std::fs::create_dir_all(dir.path().join("tools")).unwrap();
+        let workspace: PnpmWorkspaceYaml = serde_yaml::from_str(
+            "packages:\n  - \"packages/*\"\n  - \"tools\"\n  - \"apps/*\"\n  - \"!legacy\"\n  - \"my-app\"\n  - \"utils\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            package_name_patterns(dir.path(), &workspace),
+            vec!["my-app", "utils"]
+        );
+    }
+
+    #[test]
+    fn collect_packages_reports_globs_matching_only_empty_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("packages/app")).unwrap();
+        std::fs::write(
+            dir.path().join("packages/app/package.json"),
+            r#"{ "name": "app" }"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("apps/web/src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("apps/docs")).unwrap();
+        let workspace: PnpmWorkspaceYaml = serde_yaml::from_str(
+            "packages:\n  - \"packages/*\"\n  - \"apps/*\"\n  - \"tools/*\"\n",
+        )
+        .unwrap();
+
+        let (packages, empty_matches) =
+            collect_packages(dir.path(), &workspace, &LoadOptions::default()).unwrap();
+
+        assert_eq!(packages.len(), 1);
+        // tools/* matches nothing at all, which is a different problem
+        assert_eq!(empty_matches, vec!["apps/*"]);
+    }
+
+    #[test]
+    fn collect_packages_falls_back_to_package_json_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["app", "lib"] {
+            let pkg = dir.path().join("packages").join(name);
+            std::fs::create_dir_all(&pkg).unwrap();
+            std::fs::write(
+                pkg.join("package.json"),
+                format!(r#"{{ "name": "{name}" }}"#),
+            )
+            .unwrap();
+        }
+        let no_packages: PnpmWorkspaceYaml =
+            serde_yaml::from_str("catalog:\n  react: ^18.2.0\n").unwrap();
+        let names = |root_manifest: &str, workspace: &PnpmWorkspaceYaml| -> Vec<String> {
+            std::fs::write(dir.path().join("package.json"), root_manifest).unwrap();
+            let (packages, _) =
+                collect_packages(dir.path(), workspace, &LoadOptions::default()).unwrap();
+            packages
+                .iter()
+                .map(|p| p.package_type.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            names(r#"{ "workspaces": ["packages/*"] }"#, &no_packages),
+            vec!["(root)", "app", "lib"]
+        );
+        assert_eq!(
+            names(
+                r#"{ "workspaces": { "packages": ["packages/app"], "nohoist": ["**"] } }"#,
+                &no_packages
+            ),
+            vec!["(root)", "app"]
+        );
+        assert_eq!(names("{}", &no_packages), vec!["(root)"]);
+
+        // packages: in pnpm-workspace.yaml wins
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("packages:\n  - \"packages/lib\"\n").unwrap();
+        assert_eq!(
+            names(r#"{ "workspaces": ["packages/*"] }"#, &workspace),
+            vec!["(root)", "lib"]
+        );
+    }
+
+    #[test]
+    fn collect_packages_reports_which_package_json_is_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let broken = dir.path().join("packages/broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join("package.json"), r#"{ "name": "#).unwrap();
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("packages:\n  - \"packages/*\"\n").unwrap();
+
+        let err = collect_packages(dir.path(), &workspace, &LoadOptions::default()).unwrap_err();
+
+        match err {
+            LintError::PackageParse { path, .. } => {
+                assert_eq!(path, broken.join("package.json"))
+            }
+            other => panic!("expected PackageParse, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn workspace_without_root_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("packages/app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(
+            app.join("package.json"),
+            r#"{ "name": "app", "dependencies": { "react": "^18.2.0" } }"#,
+        )
+        .unwrap();
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("packages:\n  - \"packages/*\"\n").unwrap();
+
+        let (packages, empty_matches) =
+            collect_packages(dir.path(), &workspace, &LoadOptions::default()).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert!(empty_matches.is_empty());
+        assert_eq!(
+            packages[0].package_type,
+            PackageType::Workspace("app".to_string())
+        );
+
+        let catalogs = make_catalogs(vec![("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+        let (issues, fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        let attributed: Vec<(String, &str)> = issues
+            .iter()
+            .map(|(pkg, issue)| (pkg.to_string(), issue.name()))
+            .collect();
+        assert_eq!(
+            attributed,
+            vec![
+                ("app".to_string(), "no-direct-version"),
+                ("pnpm-workspace.yaml".to_string(), "unused-catalog-entry"),
+            ]
+        );
+        assert_eq!(fix.version_replacements.len(), 1);
+        assert_eq!(fix.unused_entries.len(), 1);
+    }
+
+    #[test]
+    fn catalog_enforce_public_skips_private_packages() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let mut private_pkg = make_package("private-app", vec![("react", "^18.2.0")]);
+        private_pkg.inner.private = true;
+        let packages = vec![
+            private_pkg,
+            make_package("public-lib", vec![("lodash", "^4.17.21")]),
+        ];
+
+        let (issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                catalog_enforce: CatalogEnforce::Public,
+                ..Default::default()
+            },
+        );
+
+        // Only the public package's uncataloged dependency is reported
+        assert_eq!(issues.errors_count(), 0);
+        assert_eq!(issues.warnings_count(), 1);
+        let (pkg, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "no-uncataloged-dependency");
+        assert_eq!(*pkg, PackageType::Workspace("public-lib".to_string()));
+        assert!(fix.version_replacements.is_empty());
+        // The private package's direct version still counts as a use of the entry
+        assert!(fix.unused_entries.is_empty());
+    }
+
+    #[test]
+    fn catalog_enforce_private_skips_public_packages() {
+        let catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        let mut private_pkg = make_package("private-app", vec![("react", "^18.2.0")]);
+        private_pkg.inner.private = true;
+        let packages = vec![
+            private_pkg,
+            make_package("public-lib", vec![("react", "^18.2.0")]),
+        ];
+
+        let (issues, fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                catalog_enforce: CatalogEnforce::Private,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(issues.errors_count(), 1);
+        let (pkg, _) = issues.iter().next().unwrap();
+        assert_eq!(*pkg, PackageType::Workspace("private-app".to_string()));
+        assert_eq!(fix.version_replacements.len(), 1);
+    }
+
+    #[test]
+    fn catalog_enforce_still_checks_catalog_refs() {
+        let catalogs = make_catalogs(vec![]);
+        let mut private_pkg = make_package("private-app", vec![("react", "catalog:")]);
+        private_pkg.inner.private = true;
+        let packages = vec![private_pkg];
+
+        let (issues, _fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                catalog_enforce: CatalogEnforce::Public,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(issues.errors_count(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "catalog-entry-exists");
+    }
+
+    #[test]
+    fn dangling_workspace_ref_detected() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package(
+                "app",
+                vec![("utils", "workspace:*"), ("old-lib", "workspace:^")],
+            ),
+            make_package("utils", vec![]),
+        ];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert_eq!(issues.warnings_count(), 1);
+        let (pkg, issue) = issues.iter().next().unwrap();
+        assert_eq!(*pkg, PackageType::Workspace("app".to_string()));
+        assert_eq!(issue.name(), "dangling-workspace-ref");
+        assert!(issue.message().contains("'old-lib'"));
+    }
+
+    #[test]
+    fn aliased_workspace_ref_resolves_target_name() {
+        let catalogs = make_catalogs(vec![]);
+        let packages = vec![
+            make_package("app", vec![("utils", "workspace:@acme/utils@*")]),
+            make_package("@acme/utils", vec![]),
+        ];
+
+        let (issues, _fix, _usage) = collect_issues(&packages, &catalogs, &LintOptions::default());
+
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn policy_requires_named_catalog() {
+        let mut internal = IndexMap::new();
+        internal.insert("@acme/ui".to_string(), "^2.0.0".to_string());
+        let mut catalogs = make_catalogs(vec![("@acme/icons", "^1.0.0")]);
+        catalogs.insert_named("internal".to_string(), internal);
+        let packages = vec![make_package(
+            "app",
+            vec![
+                ("@acme/ui", "catalog:internal"),
+                ("@acme/icons", "catalog:"),
+                ("@acme/app-shell", "workspace:*"),
+            ],
+        )];
+        let policy: Policy = toml::from_str(
+            r#"
+name = "acme-internal"
+dependency = "@acme/*"
+catalog = "internal"
+level = "warn"
+"#,
+        )
+        .unwrap();
+
+        let (issues, _fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Only(vec!["acme-internal".to_string()]),
+                policies: vec![policy],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(issues.warnings_count(), 1);
+        let (_, issue) = issues.iter().next().unwrap();
+        assert_eq!(issue.name(), "acme-internal");
+        assert!(issue.message().contains("'@acme/icons'"));
+        assert!(issue.message().contains("\"catalog:internal\""));
+    }
+
+    #[test]
+    fn missing_named_entry_hints_default_catalog() {
+        let mut react17 = IndexMap::new();
+        react17.insert("react-dom".to_string(), "^17.0.2".to_string());
+        let mut catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        catalogs.insert_named("react17".to_string(), react17);
+        let packages = vec![make_package(
+            "app",
+            vec![("react", "catalog:react17"), ("vue", "catalog:react17")],
+        )];
+
+        let (issues, _fix, _usage) = collect_issues(
+            &packages,
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Only(vec!["catalog-entry-exists".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].ends_with(
+            "but 'react' IS available in the default catalog — did you mean \"catalog:\"?"
+        ));
+        assert!(messages[1].ends_with("is not defined in catalog \"react17\""));
+    }
+
+    #[test]
+    fn redundant_catalog_duplication_detected() {
+        let mut catalogs = make_catalogs(vec![("react", "^18.2.0")]);
+        for name in ["web", "tools"] {
+            let mut entries = IndexMap::new();
+            entries.insert("typescript".to_string(), "^5.4.0".to_string());
+            entries.insert("react".to_string(), "^17.0.2".to_string());
+            if name == "web" {
+                entries.insert("vite".to_string(), "^5.0.0".to_string());
+            }
+            catalogs.insert_named(name.to_string(), entries);
+        }
+
+        let (issues, _fix, _usage) = collect_issues(
+            &[],
+            &catalogs,
+            &LintOptions {
+                rule_filter: Filter::Only(vec!["redundant-catalog-duplication".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react' (\"^17.0.2\") is pinned identically in catalogs \"tools\", \"web\". Consider consolidating it into the default catalog.",
+                "'typescript' (\"^5.4.0\") is pinned identically in catalogs \"tools\", \"web\". Consider consolidating it into the default catalog.",
+            ]
+        );
     }
 }
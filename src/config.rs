@@ -0,0 +1,713 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory};
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::args::Args;
+use crate::packages::{Dependency, DependencyKind, parse_catalog_ref};
+use crate::rules::catalog_overly_permissive::DEFAULT_PERMISSIVE_SPECS;
+use crate::rules::{IssueLevel, RULE_NAMES};
+use crate::workspace::CatalogEntry;
+
+/// Optional config file read from the workspace root.
+pub const CONFIG_FILE_NAME: &str = "pnpm-catalog-lint.toml";
+
+/// rc files looked up in the workspace root and every directory above it, in order of
+/// preference; at most one is read per directory. `.catalintrc` may be JSON or YAML.
+pub const RC_FILE_NAMES: &[&str] = &[".catalintrc", ".catalintrc.json", ".catalintrc.yaml"];
+
+/// Settings read from `pnpm-catalog-lint.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(default, rename = "policy")]
+    pub policies: Vec<Policy>,
+    /// Catalog entries kept on purpose while nothing references them yet, per catalog
+    /// (`default` for the default catalog). `unused-catalog-entry` skips them.
+    #[serde(default)]
+    pub reserved: IndexMap<String, Vec<String>>,
+    /// Catalog versions `catalog-overly-permissive` reports; replaces the built-in list
+    #[serde(rename = "permissive-specs")]
+    pub permissive_specs: Option<Vec<String>>,
+    /// Dependencies whose catalog entries may pin pre-release or build-metadata versions
+    #[serde(default, rename = "allow-prerelease")]
+    pub allow_prerelease: Vec<String>,
+    /// Message templates by rule or policy name, e.g. to append a link to internal docs
+    #[serde(default)]
+    pub messages: IndexMap<String, String>,
+}
+
+impl ConfigFile {
+    pub fn reserved_entries(&self) -> HashSet<CatalogEntry> {
+        self.reserved
+            .iter()
+            .flat_map(|(catalog, deps)| {
+                let catalog_name = (catalog != "default").then(|| catalog.clone());
+                deps.iter().map(move |dep| CatalogEntry {
+                    catalog_name: catalog_name.clone(),
+                    dependency_name: dep.clone(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn message_templates(&self) -> HashMap<String, String> {
+        self.messages.clone().into_iter().collect()
+    }
+
+    pub fn permissive_specs(&self) -> Vec<String> {
+        match &self.permissive_specs {
+            Some(specs) => specs.clone(),
+            None => DEFAULT_PERMISSIVE_SPECS
+                .iter()
+                .map(|spec| spec.to_string())
+                .collect(),
+        }
+    }
+
+    /// Load the config from the rc files found from the filesystem root down to the workspace
+    /// root, then `pnpm-catalog-lint.toml`. Files closer to the workspace root override
+    /// the ones above them. No file at all yields an empty config.
+    pub fn load(root: &Path) -> Result<Self> {
+        let mut merged = toml::Table::new();
+        let mut sources = rc_files(root);
+        sources.reverse();
+        for path in &sources {
+            merge_tables(&mut merged, read_rc_file(path)?);
+        }
+        let path = root.join(CONFIG_FILE_NAME);
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let table = parse_table(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            merge_tables(&mut merged, table);
+            sources.push(path);
+        }
+        Self::from_table(merged).with_context(|| match sources.as_slice() {
+            [path] => format!("Failed to parse {}", path.display()),
+            _ => format!(
+                "Invalid configuration merged from {}",
+                sources
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+    }
+
+    #[cfg(test)]
+    fn parse(content: &str) -> Result<Self> {
+        Self::from_table(parse_table(content)?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self> {
+        let config: ConfigFile = toml::Value::Table(table).try_into()?;
+        for policy in &config.policies {
+            if RULE_NAMES.contains(&policy.name.as_str()) {
+                bail!(
+                    "policy '{}' has the same name as a built-in rule",
+                    policy.name
+                );
+            }
+        }
+        for rule in config.messages.keys() {
+            let is_policy = config.policies.iter().any(|p| &p.name == rule);
+            if !RULE_NAMES.contains(&rule.as_str()) && !is_policy {
+                bail!("[messages] has a template for unknown rule '{rule}'");
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The rc file of the workspace root and of each directory above it, nearest first.
+/// `ancestors` stops after the filesystem root, so the walk always ends.
+pub fn rc_files(root: &Path) -> Vec<std::path::PathBuf> {
+    root.ancestors()
+        .filter_map(|dir| {
+            RC_FILE_NAMES
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+/// Read an rc file into the same table shape as `pnpm-catalog-lint.toml`.
+fn read_rc_file(path: &Path) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let table: toml::Table = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&content).map_err(anyhow::Error::from)
+    } else {
+        serde_yaml::from_str(&content).map_err(anyhow::Error::from)
+    }
+    .with_context(|| format!("Failed to parse {}", path.display()))?;
+    // Check each file on its own, so unknown keys are reported against the file they're in
+    toml::Value::Table(table.clone())
+        .try_into::<ConfigFile>()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(table)
+}
+
+fn parse_table(content: &str) -> Result<toml::Table> {
+    let table: toml::Table = toml::from_str(content)?;
+    toml::Value::Table(table.clone()).try_into::<ConfigFile>()?;
+    Ok(table)
+}
+
+/// Merge `child` into `base`: tables are merged key by key, anything else (arrays included)
+/// replaces the value from `base`.
+fn merge_tables(base: &mut toml::Table, child: toml::Table) {
+    for (key, value) in child {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(child)) => {
+                merge_tables(base, child)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The starter `pnpm-catalog-lint.toml` written by `init`. Everything is commented out,
+/// so the file loads as an empty config until the user opts in.
+pub fn starter_config() -> String {
+    let mut out = String::from(
+        "# pnpm-catalog-lint configuration\n\
+         #\n\
+         # Built-in rules; turn them off with --exclude-rule or narrow a run with --only-rule:\n",
+    );
+    for rule in RULE_NAMES {
+        out.push_str(&format!("#   {rule}\n"));
+    }
+    let specs: Vec<String> = DEFAULT_PERMISSIVE_SPECS
+        .iter()
+        .map(|spec| format!("{spec:?}"))
+        .collect();
+    out.push_str(&format!(
+        "\n\
+         # Catalog versions catalog-overly-permissive reports. Default:\n\
+         # permissive-specs = [{}]\n\
+         \n\
+         # Dependencies catalog-prerelease-version allows to pin pre-releases, e.g. \"2.0.0-beta.3\"\n\
+         # allow-prerelease = []\n\
+         \n\
+         # Catalog entries to keep while nothing references them yet, per catalog\n\
+         # (`default` for the default catalog). unused-catalog-entry skips them.\n\
+         # [reserved]\n\
+         # default = []\n\
+         \n\
+         # Replace a rule's message. {{message}} is the built-in message; {{rule}}, {{package}},\n\
+         # {{dependency}}, {{catalog}} and {{kind}} are filled in where the issue has them.\n\
+         # [messages]\n\
+         # no-direct-version = \"{{message}} See https://wiki.example.com/catalogs\"\n\
+         \n\
+         # Require dependencies matching a glob to come from a specific catalog.\n\
+         # [[policy]]\n\
+         # name = \"scoped-from-internal\"\n\
+         # dependency = \"@acme/*\"\n\
+         # catalog = \"internal\"\n\
+         # kinds = [\"dependencies\", \"devDependencies\"]\n\
+         # level = \"error\"\n\
+         \n\
+         # Package directories to skip go in a separate file, one glob per line:\n\
+         #   pnpm-catalog-lint --ignore-path .catalog-lint-ignore\n",
+        specs.join(", "),
+    ));
+    out
+}
+
+/// Write the starter config to the workspace root. Refuses to replace an existing file
+/// unless `force` is set.
+pub fn write_starter_config(root: &Path, force: bool) -> Result<std::path::PathBuf> {
+    let path = root.join(CONFIG_FILE_NAME);
+    if path.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+    std::fs::write(&path, starter_config())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Read an ignore file for `--ignore-path`: one package path glob per line,
+/// relative to the workspace root. Blank lines and lines starting with `#` are skipped.
+pub fn read_ignore_file(path: &Path) -> Result<Vec<glob::Pattern>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_ignore_patterns(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn parse_ignore_patterns(content: &str) -> Result<Vec<glob::Pattern>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            // "packages/legacy/" and "packages/legacy" mean the same directory
+            let line = line.trim_end_matches('/');
+            glob::Pattern::new(line).with_context(|| format!("Invalid glob pattern: {line}"))
+        })
+        .collect()
+}
+
+/// A declarative catalog rule: dependencies whose name matches `dependency`
+/// (and whose kind is listed in `kinds`, if any) must be referenced through `catalog`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// Reported as the rule name, so it works with --only-rule/--exclude-rule
+    pub name: String,
+    /// Glob matched against the dependency name, e.g. `@acme/*`
+    #[serde(deserialize_with = "deserialize_pattern")]
+    pub dependency: glob::Pattern,
+    #[serde(default)]
+    pub kinds: Vec<DependencyKind>,
+    /// Required catalog; `default` for the default catalog
+    pub catalog: String,
+    /// Error unless set. A level set here also holds under `--strict`.
+    pub level: Option<IssueLevel>,
+}
+
+fn deserialize_pattern<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<glob::Pattern, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    glob::Pattern::new(&pattern).map_err(serde::de::Error::custom)
+}
+
+impl Policy {
+    pub fn level(&self) -> IssueLevel {
+        self.level.unwrap_or(IssueLevel::Error)
+    }
+
+    pub fn applies_to(&self, dep: &Dependency) -> bool {
+        self.dependency.matches(&dep.name)
+            && (self.kinds.is_empty() || self.kinds.contains(&dep.kind))
+    }
+
+    /// The catalog: reference that satisfies this policy.
+    pub fn expected_ref(&self) -> String {
+        if self.catalog == "default" {
+            "catalog:".to_string()
+        } else {
+            format!("catalog:{}", self.catalog)
+        }
+    }
+
+    pub fn is_satisfied_by(&self, version: &str) -> bool {
+        match parse_catalog_ref(version) {
+            Some(None) => self.catalog == "default",
+            Some(Some(name)) => name == self.catalog,
+            None => false,
+        }
+    }
+}
+
+/// Where an effective option value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    Default,
+    Env,
+    Cli,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::Env => write!(f, "env"),
+            Origin::Cli => write!(f, "cli"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum OptionValue {
+    Single(Option<String>),
+    List(Vec<String>),
+}
+
+impl std::fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionValue::Single(Some(value)) => write!(f, "{value}"),
+            OptionValue::Single(None) => write!(f, "(unset)"),
+            OptionValue::List(values) => write!(f, "[{}]", values.join(", ")),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolvedOption {
+    pub name: String,
+    pub value: OptionValue,
+    pub source: Origin,
+}
+
+/// The fully-resolved configuration, as printed by `--print-config`.
+#[derive(Debug, Serialize)]
+pub struct EffectiveConfig {
+    pub options: Vec<ResolvedOption>,
+    /// Rules that will run after applying the rule filters
+    pub enabled_rules: Vec<&'static str>,
+}
+
+impl EffectiveConfig {
+    /// Resolve every CLI option, annotated with where its value came from.
+    /// Options are read generically from the clap definition so new flags show up automatically.
+    pub fn resolve(args: &Args, matches: &ArgMatches) -> Self {
+        let command = Args::command();
+        let mut options = Vec::new();
+
+        for arg in command.get_arguments() {
+            let id = arg.get_id().as_str();
+            if matches!(id, "help" | "version" | "print_config") {
+                continue;
+            }
+
+            let raw: Vec<String> = matches
+                .get_raw(id)
+                .map(|values| values.map(|v| v.to_string_lossy().to_string()).collect())
+                .unwrap_or_default();
+
+            let value = if matches!(arg.get_action(), ArgAction::Append) {
+                OptionValue::List(raw)
+            } else {
+                OptionValue::Single(raw.into_iter().next())
+            };
+
+            let source = match matches.value_source(id) {
+                Some(ValueSource::CommandLine) => Origin::Cli,
+                Some(ValueSource::EnvVariable) => Origin::Env,
+                _ => Origin::Default,
+            };
+
+            let name = arg
+                .get_long()
+                .map(|long| long.to_string())
+                .unwrap_or_else(|| id.to_string());
+
+            options.push(ResolvedOption {
+                name,
+                value,
+                source,
+            });
+        }
+
+        let rule_filter = args.rule_filter();
+        let enabled_rules = RULE_NAMES
+            .iter()
+            .copied()
+            .filter(|rule| !rule_filter.is_ignored(rule))
+            .collect();
+
+        Self {
+            options,
+            enabled_rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(argv: &[&str]) -> EffectiveConfig {
+        let matches = Args::command().get_matches_from(argv);
+        let args = <Args as clap::FromArgMatches>::from_arg_matches(&matches).unwrap();
+        EffectiveConfig::resolve(&args, &matches)
+    }
+
+    fn option<'a>(config: &'a EffectiveConfig, name: &str) -> &'a ResolvedOption {
+        config.options.iter().find(|o| o.name == name).unwrap()
+    }
+
+    fn dep(name: &str, kind: DependencyKind) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            version: "^1.0.0".to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn parses_policies() {
+        let config = ConfigFile::parse(
+            r#"
+[[policy]]
+name = "acme-internal"
+dependency = "@acme/*"
+catalog = "internal"
+
+[[policy]]
+name = "test-tools"
+dependency = "vitest"
+kinds = ["devDependencies"]
+catalog = "default"
+level = "warn"
+"#,
+        )
+        .unwrap();
+
+        let [internal, tools] = config.policies.as_slice() else {
+            panic!("expected two policies");
+        };
+        assert_eq!(internal.level(), IssueLevel::Error);
+        assert!(internal.applies_to(&dep("@acme/ui", DependencyKind::Dependencies)));
+        assert!(!internal.applies_to(&dep("react", DependencyKind::Dependencies)));
+        assert!(internal.is_satisfied_by("catalog:internal"));
+        assert!(!internal.is_satisfied_by("catalog:"));
+        assert_eq!(internal.expected_ref(), "catalog:internal");
+
+        assert_eq!(tools.level(), IssueLevel::Warning);
+        assert!(tools.applies_to(&dep("vitest", DependencyKind::DevDependencies)));
+        assert!(!tools.applies_to(&dep("vitest", DependencyKind::Dependencies)));
+        assert!(tools.is_satisfied_by("catalog:"));
+        assert!(tools.is_satisfied_by("catalog:default"));
+    }
+
+    #[test]
+    fn parses_ignore_patterns() {
+        let patterns =
+            parse_ignore_patterns("# legacy apps\npackages/legacy-*\n\n  apps/sandbox/  \n")
+                .unwrap();
+        let patterns: Vec<&str> = patterns.iter().map(|p| p.as_str()).collect();
+        assert_eq!(patterns, vec!["packages/legacy-*", "apps/sandbox"]);
+
+        assert!(parse_ignore_patterns("packages/[").is_err());
+    }
+
+    #[test]
+    fn parses_reserved_entries() {
+        let config = ConfigFile::parse(
+            r#"
+[reserved]
+default = ["@acme/new-ui"]
+react17 = ["react-dom"]
+"#,
+        )
+        .unwrap();
+
+        let reserved = config.reserved_entries();
+        assert_eq!(reserved.len(), 2);
+        assert!(reserved.contains(&CatalogEntry {
+            catalog_name: None,
+            dependency_name: "@acme/new-ui".to_string(),
+        }));
+        assert!(reserved.contains(&CatalogEntry {
+            catalog_name: Some("react17".to_string()),
+            dependency_name: "react-dom".to_string(),
+        }));
+    }
+
+    #[test]
+    fn permissive_specs_replace_the_defaults() {
+        assert!(
+            ConfigFile::default()
+                .permissive_specs()
+                .contains(&"latest".to_string())
+        );
+
+        let config = ConfigFile::parse(r#"permissive-specs = ["*", "next"]"#).unwrap();
+        assert_eq!(config.permissive_specs(), vec!["*", "next"]);
+    }
+
+    #[test]
+    fn rejects_policy_shadowing_builtin_rule() {
+        let result = ConfigFile::parse(
+            r#"
+[[policy]]
+name = "no-direct-version"
+dependency = "*"
+catalog = "default"
+"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_message_template_for_unknown_rule() {
+        let config = ConfigFile::parse(
+            r#"
+[[policy]]
+name = "acme-internal"
+dependency = "@acme/*"
+catalog = "internal"
+
+[messages]
+no-direct-version = "{message} See https://wiki.example.com"
+acme-internal = "{message}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.message_templates().len(), 2);
+
+        let err =
+            ConfigFile::parse("[messages]\nno-direct-versions = \"{message}\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[messages] has a template for unknown rule 'no-direct-versions'"
+        );
+    }
+
+    #[test]
+    fn rc_files_cascade_from_parent_to_workspace_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("apps/web");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            dir.path().join(".catalintrc.json"),
+            r#"{ "permissive-specs": ["*"], "allow-prerelease": ["typescript"], "reserved": { "default": ["react"] } }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join(".catalintrc"),
+            "reserved:\n  react17: [react-dom]\nmessages:\n  empty-catalog: \"{message} (web)\"\n",
+        )
+        .unwrap();
+        // Ignored: .catalintrc is preferred in the same directory
+        std::fs::write(root.join(".catalintrc.yaml"), "bogus: true\n").unwrap();
+        std::fs::write(
+            root.join(CONFIG_FILE_NAME),
+            "permissive-specs = [\"latest\"]\n",
+        )
+        .unwrap();
+
+        let config = ConfigFile::load(&root).unwrap();
+
+        assert_eq!(config.permissive_specs(), vec!["latest"]);
+        assert_eq!(config.allow_prerelease, vec!["typescript"]);
+        let reserved: Vec<&String> = config.reserved.keys().collect();
+        assert_eq!(reserved, vec!["default", "react17"]);
+        assert_eq!(config.messages["empty-catalog"], "{message} (web)");
+    }
+
+    #[test]
+    fn rc_file_errors_name_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".catalintrc.yaml"), "permisive-specs: []\n").unwrap();
+
+        let err = ConfigFile::load(dir.path()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Failed to parse {}",
+                dir.path().join(".catalintrc.yaml").display()
+            )
+        );
+    }
+
+    #[test]
+    fn annotates_default_and_cli_values() {
+        let config = resolve(&[
+            "pnpm-catalog-lint",
+            "--exclude-rule",
+            "unused-catalog-entry",
+        ]);
+
+        let exclude = option(&config, "exclude-rule");
+        assert_eq!(exclude.source, Origin::Cli);
+        assert_eq!(exclude.value.to_string(), "[unused-catalog-entry]");
+
+        let path = option(&config, "path");
+        assert_eq!(path.source, Origin::Default);
+        assert_eq!(path.value.to_string(), ".");
+
+        let format = option(&config, "format");
+        assert_eq!(format.source, Origin::Default);
+        assert_eq!(format.value.to_string(), "text");
+    }
+
+    #[test]
+    fn enabled_rules_respect_rule_filter() {
+        let config = resolve(&["pnpm-catalog-lint", "--only-rule", "no-direct-version"]);
+        assert_eq!(config.enabled_rules, vec!["no-direct-version"]);
+
+        let config = resolve(&["pnpm-catalog-lint"]);
+        assert_eq!(config.enabled_rules, RULE_NAMES);
+    }
+
+    #[test]
+    fn rule_shortcuts_exclude_their_rule() {
+        let config = resolve(&[
+            "pnpm-catalog-lint",
+            "--no-unused",
+            "--exclude-rule",
+            "empty-catalog",
+        ]);
+        assert!(!config.enabled_rules.contains(&"unused-catalog-entry"));
+        assert!(!config.enabled_rules.contains(&"empty-catalog"));
+        assert_eq!(config.enabled_rules.len(), RULE_NAMES.len() - 2);
+
+        let config = resolve(&[
+            "pnpm-catalog-lint",
+            "--only-rule",
+            "no-direct-version",
+            "--only-rule",
+            "catalog-entry-exists",
+            "--no-direct",
+        ]);
+        assert_eq!(config.enabled_rules, vec!["catalog-entry-exists"]);
+    }
+
+    #[test]
+    fn print_config_is_not_listed() {
+        let config = resolve(&["pnpm-catalog-lint", "--print-config"]);
+        assert!(config.options.iter().all(|o| o.name != "print-config"));
+    }
+
+    #[test]
+    fn starter_config_lists_every_rule_and_loads_empty() {
+        let starter = starter_config();
+        for rule in RULE_NAMES {
+            assert!(starter.contains(&format!("#   {rule}\n")), "{rule} missing");
+        }
+        let config = ConfigFile::parse(&starter).unwrap();
+        assert!(config.policies.is_empty());
+        assert!(config.reserved.is_empty());
+        assert!(config.permissive_specs.is_none());
+        assert!(config.messages.is_empty());
+
+        // Uncommenting the examples yields a valid config with the same defaults
+        let uncommented: String = starter
+            .lines()
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| line.starts_with('[') || line.contains(" = "))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let config = ConfigFile::parse(&uncommented).unwrap();
+        assert_eq!(config.policies.len(), 1);
+        assert_eq!(config.messages.len(), 1);
+        assert_eq!(
+            config.permissive_specs(),
+            ConfigFile::default().permissive_specs()
+        );
+    }
+
+    #[test]
+    fn starter_config_is_not_overwritten_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "# mine\n").unwrap();
+
+        let err = write_starter_config(dir.path(), false).unwrap_err();
+        assert!(err.to_string().contains("pass --force"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# mine\n");
+
+        write_starter_config(dir.path(), true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), starter_config());
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::Path;
+
+use crate::collect::{LoadOptions, collect_packages};
+use crate::config::{CONFIG_FILE_NAME, ConfigFile, rc_files};
+use crate::packages::{Package, PackageJson, PackageManager};
+use crate::workspace::{PnpmWorkspaceYaml, parse_workspace};
+
+/// Top-level keys pnpm reads from pnpm-workspace.yaml that this tool cares about.
+const KNOWN_WORKSPACE_KEYS: &[&str] = &["packages", "catalog", "catalogs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl Check {
+    fn pass(message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Pass,
+            message: message.into(),
+        }
+    }
+
+    fn warn(message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            message: message.into(),
+        }
+    }
+
+    fn fail(message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            message: message.into(),
+        }
+    }
+}
+
+/// Check a workspace for common setup problems without running the lint rules.
+pub fn run(root: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let (workspace, catalogs) = match parse_workspace(root) {
+        Ok(parsed) => {
+            checks.push(Check::pass("pnpm-workspace.yaml found and parsed"));
+            parsed
+        }
+        Err(e) => {
+            checks.push(Check::fail(format!("{:#}", anyhow::Error::from(e))));
+            return checks;
+        }
+    };
+
+    // Misspelled top-level keys are silently ignored by the YAML parser
+    if let Ok(content) = std::fs::read_to_string(root.join("pnpm-workspace.yaml"))
+        && let Ok(serde_yaml::Value::Mapping(mapping)) = serde_yaml::from_str(&content)
+    {
+        for key in mapping.keys().filter_map(|k| k.as_str()) {
+            if let Some(known) = likely_typo_of(key) {
+                checks.push(Check::warn(format!(
+                    "pnpm-workspace.yaml has a \"{key}\" key; did you mean \"{known}\"?"
+                )));
+            }
+        }
+    }
+
+    if workspace.packages.is_empty() {
+        checks.push(Check::warn("No packages globs in pnpm-workspace.yaml"));
+    }
+    for pattern in workspace.packages.iter().filter(|p| !p.starts_with('!')) {
+        let single = PnpmWorkspaceYaml {
+            packages: vec![pattern.clone()],
+            ..Default::default()
+        };
+        match collect_packages(root, &single, &LoadOptions::default()) {
+            Ok((packages, _)) => {
+                let count = packages.iter().filter(|pkg| pkg.path != root).count();
+                if count == 0 {
+                    checks.push(Check::warn(format!(
+                        "Glob \"{pattern}\" matches no packages"
+                    )));
+                } else {
+                    checks.push(Check::pass(format!(
+                        "Glob \"{pattern}\" matches {count} package{}",
+                        if count == 1 { "" } else { "s" }
+                    )));
+                }
+            }
+            Err(e) => checks.push(Check::fail(format!("{:#}", anyhow::Error::from(e)))),
+        }
+    }
+
+    let entries = catalogs.all_entries().len();
+    let catalog_count = catalogs.named().len() + usize::from(!catalogs.default().is_empty());
+    if entries == 0 {
+        checks.push(Check::warn(
+            "No catalogs defined; add a catalog: or catalogs: section to pnpm-workspace.yaml",
+        ));
+    } else {
+        checks.push(Check::pass(format!(
+            "{entries} catalog entr{} across {catalog_count} catalog{}",
+            if entries == 1 { "y" } else { "ies" },
+            if catalog_count == 1 { "" } else { "s" },
+        )));
+    }
+
+    if root.join("package.json").exists() {
+        match Package::load(root, true) {
+            Ok(pkg) => checks.push(check_package_manager(&pkg.inner)),
+            Err(e) => checks.push(Check::fail(format!("{:#}", anyhow::Error::from(e)))),
+        }
+    } else {
+        checks.push(Check::warn(
+            "No root package.json; can't check the packageManager field",
+        ));
+    }
+
+    let rc_files = rc_files(root);
+    if root.join(CONFIG_FILE_NAME).exists() || !rc_files.is_empty() {
+        match ConfigFile::load(root) {
+            Ok(_) if rc_files.is_empty() => {
+                checks.push(Check::pass(format!("{CONFIG_FILE_NAME} is valid")))
+            }
+            Ok(_) => checks.push(Check::pass(format!(
+                "Configuration is valid ({} rc file{})",
+                rc_files.len(),
+                if rc_files.len() == 1 { "" } else { "s" }
+            ))),
+            Err(e) => checks.push(Check::fail(format!("{e:#}"))),
+        }
+    }
+
+    checks
+}
+
+fn check_package_manager(root_pkg: &PackageJson) -> Check {
+    match &root_pkg.package_manager {
+        Some(PackageManager::Spec(pm)) if pm.starts_with("pnpm@") => {
+            Check::pass(format!("packageManager is {pm}"))
+        }
+        Some(PackageManager::Spec(pm)) => Check::warn(format!(
+            "packageManager is {pm}; catalogs are a pnpm feature"
+        )),
+        Some(PackageManager::Invalid(value)) => Check::fail(format!(
+            "packageManager should be a string like \"pnpm@9.1.0\", got {value}"
+        )),
+        None => Check::warn("Root package.json has no packageManager field"),
+    }
+}
+
+/// A key one or two edits away from a known key, e.g. "catalogue" or "pacakges".
+fn likely_typo_of(key: &str) -> Option<&'static str> {
+    if KNOWN_WORKSPACE_KEYS.contains(&key) {
+        return None;
+    }
+    KNOWN_WORKSPACE_KEYS
+        .iter()
+        .copied()
+        .find(|known| edit_distance(&key.to_lowercase(), known) <= 2)
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr.push((prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses(checks: &[Check]) -> Vec<(CheckStatus, &str)> {
+        checks
+            .iter()
+            .map(|c| (c.status, c.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn flags_typos_empty_globs_and_package_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pnpm-workspace.yaml"),
+            "packages:\n  - \"packages/*\"\n  - \"apps/*\"\n\ncatalogue:\n  react: \"^18.2.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{ "name": "root", "packageManager": "yarn@4.1.0" }"#,
+        )
+        .unwrap();
+        let app = dir.path().join("packages/app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(app.join("package.json"), r#"{ "name": "app" }"#).unwrap();
+
+        let checks = run(dir.path());
+
+        assert_eq!(
+            statuses(&checks),
+            vec![
+                (CheckStatus::Pass, "pnpm-workspace.yaml found and parsed"),
+                (
+                    CheckStatus::Warn,
+                    "pnpm-workspace.yaml has a \"catalogue\" key; did you mean \"catalog\"?"
+                ),
+                (CheckStatus::Pass, "Glob \"packages/*\" matches 1 package"),
+                (CheckStatus::Warn, "Glob \"apps/*\" matches no packages"),
+                (
+                    CheckStatus::Warn,
+                    "No catalogs defined; add a catalog: or catalogs: section to pnpm-workspace.yaml"
+                ),
+                (
+                    CheckStatus::Warn,
+                    "packageManager is yarn@4.1.0; catalogs are a pnpm feature"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn package_manager_that_is_not_a_string_fails() {
+        let root: PackageJson =
+            serde_json::from_str(r#"{ "packageManager": ["pnpm@9.1.0"] }"#).unwrap();
+        let check = check_package_manager(&root);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(
+            check.message,
+            "packageManager should be a string like \"pnpm@9.1.0\", got [\"pnpm@9.1.0\"]"
+        );
+    }
+
+    #[test]
+    fn missing_workspace_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let checks = run(dir.path());
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn typo_detection() {
+        assert_eq!(likely_typo_of("catalogue"), Some("catalog"));
+        assert_eq!(likely_typo_of("pacakges"), Some("packages"));
+        assert_eq!(likely_typo_of("catalogs"), None);
+        assert_eq!(likely_typo_of("onlyBuiltDependencies"), None);
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use similar::TextDiff;
+
+use crate::workspace::relative_to;
+
+/// File rewrites made by `--fix`, held in memory until they're written to disk or
+/// rendered as a patch. Each fix step reads the result of the previous one.
+#[derive(Debug, Default)]
+pub struct FileEdits {
+    /// Original and current content of every file read so far, in read order
+    files: IndexMap<PathBuf, (String, String)>,
+}
+
+impl FileEdits {
+    /// Current content of a file, including earlier edits. Read from disk on first access.
+    pub fn read(&mut self, path: &Path) -> Result<String> {
+        if let Some((_, current)) = self.files.get(path) {
+            return Ok(current.clone());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.files
+            .insert(path.to_path_buf(), (content.clone(), content.clone()));
+        Ok(content)
+    }
+
+    /// Replace the content of a file previously returned by `read`.
+    pub fn write(&mut self, path: &Path, content: String) {
+        if let Some((_, current)) = self.files.get_mut(path) {
+            *current = content;
+        }
+    }
+
+    fn changed(&self) -> impl Iterator<Item = (&PathBuf, &String, &String)> {
+        self.files
+            .iter()
+            .filter(|(_, (original, current))| original != current)
+            .map(|(path, (original, current))| (path, original, current))
+    }
+
+    /// Write every changed file to disk.
+    pub fn write_all(&self) -> Result<()> {
+        for (path, _, current) in self.changed() {
+            std::fs::write(path, current)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// A unified diff of every changed file, with `a/` and `b/` paths relative to `base`
+    /// so it applies with `git apply` from that directory.
+    pub fn unified_diff(&self, base: &Path) -> String {
+        let mut patch = String::new();
+        for (path, original, current) in self.changed() {
+            let relative = relative_to(path, base);
+            let relative = relative.display();
+            let (old, new) = (format!("a/{relative}"), format!("b/{relative}"));
+            patch.push_str(&format!("diff --git {old} {new}\n"));
+            patch.push_str(
+                &TextDiff::from_lines(original, current)
+                    .unified_diff()
+                    .header(&old, &new)
+                    .to_string(),
+            );
+        }
+        patch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_reads_see_earlier_writes_and_disk_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.json");
+        std::fs::write(&path, "{\n  \"name\": \"app\"\n}\n").unwrap();
+        std::fs::write(dir.path().join("other.json"), "{}\n").unwrap();
+
+        let mut edits = FileEdits::default();
+        let content = edits.read(&path).unwrap();
+        edits.write(&path, content.replace("app", "web"));
+        edits.read(&dir.path().join("other.json")).unwrap();
+
+        assert_eq!(edits.read(&path).unwrap(), "{\n  \"name\": \"web\"\n}\n");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n  \"name\": \"app\"\n}\n"
+        );
+        assert_eq!(
+            edits.unified_diff(dir.path()),
+            "diff --git a/package.json b/package.json\n\
+             --- a/package.json\n\
+             +++ b/package.json\n\
+             @@ -1,3 +1,3 @@\n \
+             {\n\
+             -  \"name\": \"app\"\n\
+             +  \"name\": \"web\"\n \
+             }\n"
+        );
+
+        edits.write_all().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n  \"name\": \"web\"\n}\n"
+        );
+    }
+}
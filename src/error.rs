@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Failures while reading a workspace from disk, distinct enough to match on.
+#[derive(Debug, Error)]
+pub enum LintError {
+    /// The root has no pnpm-workspace.yaml
+    #[error("No pnpm-workspace.yaml found in {}", .0.display())]
+    WorkspaceNotFound(PathBuf),
+
+    #[error("Failed to parse {}", path.display())]
+    WorkspaceParse {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    /// Both `catalog` and `catalogs.default` have entries; pnpm refuses this too
+    #[error(
+        "The default catalog is defined twice in {}: use `catalog` or `catalogs.default`, not both",
+        .0.display()
+    )]
+    DuplicateDefaultCatalog(PathBuf),
+
+    /// A package.json that isn't valid JSON or has fields of the wrong type
+    #[error("Failed to parse {}", path.display())]
+    PackageParse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid glob pattern: {pattern}")]
+    InvalidGlob {
+        pattern: String,
+        #[source]
+        source: glob::PatternError,
+    },
+
+    #[error("Failed to read {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+
+use crate::packages::Package;
+
+/// Start of a `--since` window: a duration back from now ("7d", "2w", "12h", "30m")
+/// or a UTC date ("2024-05-01").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    Ago(Duration),
+    /// Unix time of midnight UTC on the date
+    Date(i64),
+}
+
+impl Since {
+    /// The cutoff as unix time, given the current unix time.
+    pub fn cutoff(self, now: i64) -> i64 {
+        match self {
+            Since::Ago(ago) => now.saturating_sub(ago.as_secs() as i64),
+            Since::Date(time) => time,
+        }
+    }
+}
+
+impl FromStr for Since {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(date) = parse_date(s) {
+            return Ok(Since::Date(date));
+        }
+        let invalid = || {
+            format!(
+                "expected a duration like 7d, 2w, 12h, 30m or a date like 2024-05-01, got '{s}'"
+            )
+        };
+        let unit_at = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let (count, unit) = s.split_at(unit_at);
+        let count: u64 = count.parse().map_err(|_| invalid())?;
+        let unit_secs = match unit {
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        // Past what a unix time can go back, the window has no meaning
+        let secs = count
+            .checked_mul(unit_secs)
+            .filter(|secs| i64::try_from(*secs).is_ok())
+            .ok_or_else(|| format!("'{s}' reaches back further than any date"))?;
+        Ok(Since::Ago(Duration::from_secs(secs)))
+    }
+}
+
+/// "YYYY-MM-DD" → unix time of midnight UTC.
+fn parse_date(s: &str) -> Option<i64> {
+    let mut parts = s.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if s.len() != 10 || !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) {
+        return None;
+    }
+    // Days from civil date, per Howard Hinnant's algorithm
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some((era * 146097 + doe - 719468) * 24 * 60 * 60)
+}
+
+/// Packages whose package.json hasn't changed since `cutoff` (unix time), by directory.
+/// Uses the last commit touching each manifest; uncommitted changes count as recent.
+/// Outside a git repository, the file's modification time is used instead.
+pub fn unchanged_since(root: &Path, packages: &[Package], cutoff: i64) -> Result<HashSet<PathBuf>> {
+    let in_repository = git(root, &["rev-parse", "--is-inside-work-tree"], None).is_ok();
+    let mut unchanged = HashSet::new();
+    for pkg in packages {
+        let manifest = pkg.path.join("package.json");
+        let modified = if in_repository {
+            let relative = manifest.strip_prefix(root).unwrap_or(&manifest);
+            last_commit_time(root, relative)?
+        } else {
+            let mtime = std::fs::metadata(&manifest)
+                .and_then(|metadata| metadata.modified())
+                .with_context(|| {
+                    format!(
+                        "Failed to read the modification time of {}",
+                        manifest.display()
+                    )
+                })?;
+            mtime
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs() as i64)
+        };
+        if modified.is_some_and(|time| time < cutoff) {
+            unchanged.insert(pkg.path.clone());
+        }
+    }
+    Ok(unchanged)
+}
+
+/// Current unix time.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Unix time of the last commit touching `path` (relative to `root`).
+/// Returns `None` for files with uncommitted changes or that were never committed,
+/// since those are newer than any commit.
+pub fn last_commit_time(root: &Path, path: &Path) -> Result<Option<i64>> {
+    let status = git(root, &["status", "--porcelain", "--"], Some(path))?;
+    if !status.is_empty() {
+        return Ok(None);
+    }
+
+    let time = git(root, &["log", "-1", "--format=%ct", "--"], Some(path))?;
+    if time.is_empty() {
+        return Ok(None);
+    }
+    time.parse()
+        .map(Some)
+        .with_context(|| format!("Unexpected git log output: {time}"))
+}
+
+fn git(root: &Path, args: &[&str], path: Option<&Path>) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .args(path)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn committed_files_have_a_time_and_dirty_files_do_not() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .env("GIT_COMMITTER_DATE", "1700000000 +0000")
+                .env("GIT_AUTHOR_DATE", "1700000000 +0000")
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "-q"]);
+        std::fs::write(root.join("package.json"), "{}").unwrap();
+        run(&["add", "package.json"]);
+        run(&["commit", "-q", "-m", "init"]);
+        std::fs::write(root.join("new.json"), "{}").unwrap();
+
+        assert_eq!(
+            last_commit_time(root, Path::new("package.json")).unwrap(),
+            Some(1700000000)
+        );
+        assert_eq!(last_commit_time(root, Path::new("new.json")).unwrap(), None);
+
+        std::fs::write(root.join("package.json"), "{ }").unwrap();
+        assert_eq!(
+            last_commit_time(root, Path::new("package.json")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_since() {
+        let day = 24 * 60 * 60;
+        assert_eq!("7d".parse(), Ok(Since::Ago(Duration::from_secs(7 * day))));
+        assert_eq!("2w".parse(), Ok(Since::Ago(Duration::from_secs(14 * day))));
+        assert_eq!(
+            "12h".parse(),
+            Ok(Since::Ago(Duration::from_secs(12 * 3600)))
+        );
+        assert_eq!("1970-01-02".parse(), Ok(Since::Date(day as i64)));
+        assert_eq!("2024-03-01".parse(), Ok(Since::Date(1709251200)));
+        assert!("7".parse::<Since>().is_err());
+        assert!("7y".parse::<Since>().is_err());
+        assert!("2024-13-01".parse::<Since>().is_err());
+        assert_eq!("2024-02-29".parse(), Ok(Since::Date(1709164800)));
+        assert!("2023-02-29".parse::<Since>().is_err());
+        assert!("2024-02-31".parse::<Since>().is_err());
+        assert!("2024-04-31".parse::<Since>().is_err());
+        assert_eq!(
+            "999999999999999w".parse::<Since>(),
+            Err("'999999999999999w' reaches back further than any date".to_string())
+        );
+        assert_eq!(Since::Ago(Duration::from_secs(60)).cutoff(1000), 940);
+    }
+
+    #[test]
+    fn outside_a_repository_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(last_commit_time(dir.path(), Path::new("package.json")).is_err());
+    }
+}
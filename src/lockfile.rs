@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use nodejs_semver::{Range, Version};
+use serde::Deserialize;
+
+use crate::collect::LintOptions;
+use crate::packages::{PackageType, alias_target};
+use crate::rules::IssuesList;
+use crate::rules::lockfile_drift::LockfileDriftIssue;
+use crate::workspace::WorkspaceCatalogs;
+
+/// Only the `packages` keys are read; their values (resolutions, dependencies…) are skipped.
+#[derive(Deserialize)]
+struct PnpmLockYaml {
+    #[serde(default)]
+    packages: HashMap<String, serde::de::IgnoredAny>,
+}
+
+/// Every version the lockfile resolved, per package name.
+#[derive(Debug, Default)]
+pub struct LockfileVersions {
+    resolved: HashMap<String, Vec<Version>>,
+}
+
+impl LockfileVersions {
+    /// Read `pnpm-lock.yaml` from the workspace root. Returns `None` when there is no lockfile.
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join("pnpm-lock.yaml");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+            .map(Some)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let lock: PnpmLockYaml = serde_yaml::from_str(content)?;
+        let mut resolved: HashMap<String, Vec<Version>> = HashMap::new();
+        for key in lock.packages.keys() {
+            if let Some((name, version)) = parse_package_key(key) {
+                resolved.entry(name.to_string()).or_default().push(version);
+            }
+        }
+        // The same version shows up once per peer dependency combination
+        for versions in resolved.values_mut() {
+            versions.sort();
+            versions.dedup();
+        }
+        Ok(Self { resolved })
+    }
+
+    pub fn versions(&self, name: &str) -> Option<&[Version]> {
+        self.resolved.get(name).map(|v| v.as_slice())
+    }
+}
+
+/// Split a `packages` key into name and version.
+/// - "react@18.2.0" → ("react", 18.2.0) — lockfile v9
+/// - "/@types/react@18.2.0" → ("@types/react", 18.2.0) — lockfile v6
+/// - "react-dom@18.2.0(react@18.2.0)" → ("react-dom", 18.2.0) — peer suffix dropped
+///
+/// Keys whose version isn't semver (git, tarball URLs) return `None`.
+fn parse_package_key(key: &str) -> Option<(&str, Version)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    let key = key.split('(').next()?;
+    let at = key.rfind('@').filter(|&at| at > 0)?;
+    let version = Version::parse(&key[at + 1..]).ok()?;
+    Some((&key[..at], version))
+}
+
+/// Warn about catalog entries whose range matches none of the versions the lockfile resolved.
+/// Dependencies that don't appear in the lockfile at all are skipped.
+pub fn check_drift(
+    catalogs: &WorkspaceCatalogs,
+    options: &LintOptions,
+    lockfile: &LockfileVersions,
+    issues: &mut IssuesList,
+) {
+    for (catalog_name, dependency_name, version) in catalogs.entries() {
+        if !options.is_catalog_selected(catalog_name.map(String::as_str)) {
+            continue;
+        }
+        // The lockfile lists npm: aliases under the published name
+        let (published_name, range) = alias_target(dependency_name, version);
+        let Ok(range) = Range::parse(range) else {
+            continue;
+        };
+        let Some(resolved) = lockfile.versions(published_name) else {
+            continue;
+        };
+        if resolved.iter().any(|v| v.satisfies(&range)) {
+            continue;
+        }
+
+        issues.add(
+            PackageType::Catalog,
+            Box::new(LockfileDriftIssue {
+                dependency_name: dependency_name.clone(),
+                catalog_name: catalog_name.cloned(),
+                version: version.clone(),
+                resolved: resolved.iter().map(|v| v.to_string()).collect(),
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Filter;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn parses_package_keys() {
+        let parsed = |key| parse_package_key(key).map(|(name, v)| (name, v.to_string()));
+        assert_eq!(
+            parsed("react@18.2.0"),
+            Some(("react", "18.2.0".to_string()))
+        );
+        assert_eq!(
+            parsed("/@types/react@18.2.0"),
+            Some(("@types/react", "18.2.0".to_string()))
+        );
+        assert_eq!(
+            parsed("react-dom@18.2.0(react@18.2.0)"),
+            Some(("react-dom", "18.2.0".to_string()))
+        );
+        assert_eq!(
+            parsed("my-fork@https://codeload.github.com/a/b/tar.gz/abc"),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_ranges_the_lockfile_does_not_satisfy() {
+        let lockfile = LockfileVersions::parse(
+            r#"
+lockfileVersion: '9.0'
+importers:
+  .: {}
+packages:
+  react@17.0.2:
+    resolution: {integrity: sha512-abc}
+  react-dom@17.0.2(react@17.0.2):
+    resolution: {integrity: sha512-def}
+  lodash@4.17.21:
+    resolution: {integrity: sha512-ghi}
+"#,
+        )
+        .unwrap();
+
+        let mut default = IndexMap::new();
+        default.insert("react".to_string(), "^18.2.0".to_string());
+        default.insert("lodash".to_string(), "^4.17.0".to_string());
+        default.insert("vite".to_string(), "^5.0.0".to_string());
+        default.insert("react-17".to_string(), "npm:react@^17.0.0".to_string());
+        default.insert("lodash-3".to_string(), "npm:lodash@^3.0.0".to_string());
+        let catalogs = WorkspaceCatalogs::new(default, IndexMap::new());
+
+        let mut issues = IssuesList::new(Filter::None);
+        check_drift(&catalogs, &LintOptions::default(), &lockfile, &mut issues);
+
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react' (\"^18.2.0\") in the default catalog matches none of the versions in pnpm-lock.yaml (17.0.2)",
+                "'lodash-3' (\"npm:lodash@^3.0.0\") in the default catalog matches none of the versions in pnpm-lock.yaml (4.17.21)",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_selected_catalogs_are_checked() {
+        let lockfile =
+            LockfileVersions::parse("packages:\n  react@17.0.2:\n    resolution: {}\n").unwrap();
+        let mut default = IndexMap::new();
+        default.insert("react".to_string(), "^18.2.0".to_string());
+        let mut legacy = IndexMap::new();
+        legacy.insert("react".to_string(), "^16.14.0".to_string());
+        let catalogs =
+            WorkspaceCatalogs::new(default, IndexMap::from([("legacy".to_string(), legacy)]));
+
+        let mut issues = IssuesList::new(Filter::None);
+        let options = LintOptions {
+            only_catalogs: vec!["legacy".to_string()],
+            ..Default::default()
+        };
+        check_drift(&catalogs, &options, &lockfile, &mut issues);
+
+        let catalogs: Vec<Option<String>> = issues.iter().map(|(_, i)| i.catalog()).collect();
+        assert_eq!(catalogs, [Some("legacy".to_string())]);
+    }
+}
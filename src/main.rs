@@ -1,131 +1,747 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::process;
 use std::time::Instant;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 
 mod args;
 mod collect;
+mod config;
+mod doctor;
+mod edits;
+mod error;
+mod git;
+mod lockfile;
+mod net;
 mod packages;
 mod printer;
+mod reference;
+mod registry;
 mod rules;
+mod stats;
 mod workspace;
 
+/// A fix applied in memory by `--fix`, reported once the files are written.
+enum FixStep {
+    CatalogAdditions { added: usize, replaced: usize },
+    Versions(usize),
+    UnusedEntries(usize),
+}
+
+impl FixStep {
+    /// The rule whose issues the step resolves
+    fn rule(&self) -> &'static str {
+        match self {
+            FixStep::CatalogAdditions { .. } => "no-uncataloged-dependency",
+            FixStep::Versions(_) => "no-direct-version",
+            FixStep::UnusedEntries(_) => "unused-catalog-entry",
+        }
+    }
+
+    fn print(&self, out: &mut impl Write) -> io::Result<()> {
+        match *self {
+            FixStep::CatalogAdditions { added, replaced } => {
+                printer::print_fixed_catalog_additions(out, added, replaced)
+            }
+            FixStep::Versions(count) => printer::print_fixed_versions(out, count),
+            FixStep::UnusedEntries(count) => printer::print_fixed(out, count),
+        }
+    }
+}
+
 fn main() {
-    let args = args::Args::parse();
+    let matches = args::Args::command().get_matches();
+    let args = args::Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     if args.no_color {
         colored::control::set_override(false);
     }
 
     let is_json = matches!(args.format, args::OutputFormat::Json);
+    // Machine-readable formats get nothing on stdout but the report itself
+    let is_text = matches!(args.format, args::OutputFormat::Text);
     let is_quiet = args.quiet;
 
+    if let Some(args::Command::Doctor { path }) = &args.command {
+        let root = Path::new(path);
+        let checks = doctor::run(root);
+        let _ = printer::print_checks(&mut io::stdout(), &checks);
+        let failed = checks
+            .iter()
+            .any(|check| check.status == doctor::CheckStatus::Fail);
+        process::exit(i32::from(failed));
+    }
+
+    if let Some(args::Command::Init { path, force }) = &args.command {
+        match config::write_starter_config(Path::new(path), *force) {
+            Ok(written) => {
+                let _ = printer::print_config_written(&mut io::stdout(), &written);
+                process::exit(0);
+            }
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(args::Command::CatalogReport {
+        path,
+        format,
+        compact,
+    }) = &args.command
+    {
+        if !matches!(format, args::OutputFormat::Text | args::OutputFormat::Json) {
+            printer::print_error(
+                &mut io::stderr(),
+                "catalog-report supports --format text or json",
+            );
+            process::exit(1);
+        }
+        let report = match collect::catalog_report(Path::new(path)) {
+            Ok(report) => report,
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+                process::exit(1);
+            }
+        };
+        let _ = match format {
+            args::OutputFormat::Json => {
+                printer::print_catalog_report_json(&mut io::stdout(), &report, !compact)
+            }
+            _ => printer::print_catalog_report(&mut io::stdout(), &report),
+        };
+        process::exit(0);
+    }
+
+    if args.print_config {
+        let config = config::EffectiveConfig::resolve(&args, &matches);
+        if is_json {
+            let _ = printer::print_config_json(&mut io::stdout(), &config, !args.compact);
+        } else {
+            let _ = printer::print_config(&mut io::stdout(), &config);
+        }
+        process::exit(0);
+    }
+
     let start = Instant::now();
 
-    let root = match std::path::Path::new(&args.path).canonicalize() {
+    // With --catalog-only, the yaml file's directory stands in for the workspace root
+    let root_arg = match &args.catalog_only {
+        Some(file) => Path::new(file).parent().unwrap_or(Path::new(".")),
+        None => Path::new(&args.path),
+    };
+    let root_arg = if root_arg.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        root_arg
+    };
+    let root = match root_arg.canonicalize() {
         Ok(p) => p,
         Err(e) => {
-            printer::print_error(&format!("Invalid path '{}': {e}", args.path));
+            printer::print_error(
+                &mut io::stderr(),
+                &format!("Invalid path '{}': {e}", root_arg.display()),
+            );
             process::exit(1);
         }
     };
 
-    let (workspace_yaml, catalogs) = match workspace::parse_workspace(&root) {
+    let workspace_file = args.catalog_only.as_ref().or(args.workspace_file.as_ref());
+    let parsed = match workspace_file {
+        Some(file) => workspace::parse_workspace_file(Path::new(file)),
+        None => workspace::parse_workspace(&root),
+    };
+    let (workspace_yaml, mut catalogs) = match parsed {
         Ok(result) => result,
         Err(e) => {
-            printer::print_error(&format!("{e:#}"));
+            printer::print_error(&mut io::stderr(), &format!("{:#}", anyhow::Error::from(e)));
             process::exit(1);
         }
     };
+    if let Some(source) = &args.catalog_source {
+        match workspace::load_catalog_source(Path::new(source)) {
+            Ok(default) => catalogs = catalogs.with_default(default),
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+                process::exit(1);
+            }
+        }
+    }
 
-    let packages = match collect::collect_packages(&root, &workspace_yaml) {
-        Ok(pkgs) => pkgs,
+    let config_file = match config::ConfigFile::load(&root) {
+        Ok(config) => config,
         Err(e) => {
-            printer::print_error(&format!("{e:#}"));
+            printer::print_error(&mut io::stderr(), &format!("{e:#}"));
             process::exit(1);
         }
     };
 
-    let (mut issues, fix) = collect::collect_issues(
-        &packages,
-        &catalogs,
-        args.rule_filter(),
-        &args.package_filter(),
-        &args.dependency_filter(),
-    );
+    let ignored_paths = match &args.ignore_path {
+        Some(path) => match config::read_ignore_file(Path::new(path)) {
+            Ok(patterns) => patterns,
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+                process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
+    let load_options = collect::LoadOptions {
+        threads: args.threads,
+        ignored_paths,
+    };
 
-    if args.fix && !fix.catalog_additions.is_empty() {
-        match workspace::add_catalog_entries(&root, &fix.catalog_additions) {
-            Ok(added) => match packages::replace_versions(&fix.catalog_addition_replacements) {
-                Ok(replaced) => {
-                    if !is_quiet && !is_json {
-                        printer::print_fixed_catalog_additions(added, replaced);
-                    }
-                    issues.remove_by_rule("no-uncataloged-dependency");
+    let loaded = match &args.from_tarball {
+        Some(tarball) => {
+            packages::Package::from_tarball(Path::new(tarball)).map(|pkg| (vec![pkg], Vec::new()))
+        }
+        None if args.catalog_only.is_some() => Ok((Vec::new(), Vec::new())),
+        None => collect::collect_packages(&root, &workspace_yaml, &load_options)
+            .map_err(anyhow::Error::from),
+    };
+    let (packages, empty_matches) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+            process::exit(1);
+        }
+    };
+
+    if args.warn_empty_matches {
+        for pattern in empty_matches {
+            printer::print_warning(
+                &mut io::stderr(),
+                &format!(
+                    "packages: glob \"{pattern}\" matches directories, but none has a package.json"
+                ),
+            );
+        }
+    }
+
+    if args.from_tarball.is_none() && args.catalog_only.is_none() {
+        for pattern in collect::package_name_patterns(&root, &workspace_yaml) {
+            printer::print_warning(
+                &mut io::stderr(),
+                &format!(
+                    "packages: entry \"{pattern}\" in pnpm-workspace.yaml matches no directory; \
+                     entries are directory globs like \"packages/*\", not package names"
+                ),
+            );
+        }
+    }
+
+    let unknown = collect::unknown_packages(&packages, &args.only_packages);
+    if !unknown.is_empty() {
+        printer::print_error(
+            &mut io::stderr(),
+            &format!(
+                "--only-package: no package named {} in the workspace",
+                unknown
+                    .iter()
+                    .map(|name| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+        process::exit(1);
+    }
+
+    let unknown_catalogs: Vec<String> = args
+        .only_catalogs
+        .iter()
+        .filter(|name| *name != "default" && !catalogs.has_catalog(name))
+        .map(|name| format!("'{name}'"))
+        .collect();
+    if !unknown_catalogs.is_empty() {
+        printer::print_error(
+            &mut io::stderr(),
+            &format!(
+                "--catalog: no catalog named {} in the workspace",
+                unknown_catalogs.join(", ")
+            ),
+        );
+        process::exit(1);
+    }
+
+    // Paths in the report are relative to --relative-to, or the workspace root by default
+    let display_base = match &args.relative_to {
+        Some(dir) => match Path::new(dir).canonicalize() {
+            Ok(p) => p,
+            Err(e) => {
+                printer::print_error(
+                    &mut io::stderr(),
+                    &format!("Invalid --relative-to '{dir}': {e}"),
+                );
+                process::exit(1);
+            }
+        },
+        None => root.clone(),
+    };
+
+    if args.dry_run {
+        let config = config::EffectiveConfig::resolve(&args, &matches);
+        let dry_run = printer::DryRun {
+            root: &root,
+            packages: &packages,
+            catalogs: &catalogs,
+            config: &config,
+            base: &display_base,
+        };
+        let _ = if is_json {
+            printer::print_dry_run_json(&mut io::stdout(), &dry_run, !args.compact)
+        } else {
+            printer::print_dry_run(&mut io::stdout(), &dry_run)
+        };
+        process::exit(0);
+    }
+
+    if args.verbose {
+        let _ = printer::print_package_sources(&mut io::stderr(), &packages, &display_base);
+    }
+
+    let mut lint_options = args.lint_options(&config_file);
+    lint_options.relative_to = Some(display_base.clone());
+    if let Some(since) = args.since {
+        match git::unchanged_since(&root, &packages, since.cutoff(git::now())) {
+            Ok(unchanged) => lint_options.unchanged_packages = unchanged,
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("--since: {e:#}"));
+                process::exit(1);
+            }
+        }
+    }
+    // Print package issues as they're found; workspace-level issues and the footer come last
+    let streaming = args.stream && is_text && !is_quiet;
+    let mut streamed = 0;
+    let (mut issues, fix, usage) =
+        collect::collect_issues_with(&packages, &catalogs, &lint_options, |pkg_issues| {
+            if streaming && !pkg_issues.is_empty() {
+                let _ = printer::print_grouped(
+                    &mut io::stdout().lock(),
+                    pkg_issues.iter(),
+                    false,
+                    None,
+                );
+                streamed += pkg_issues.len();
+            }
+        });
+
+    // Without packages, only the rules that look at the catalogs themselves are meaningful
+    if args.catalog_only.is_some() {
+        for rule in rules::RULE_NAMES {
+            if !rules::CATALOG_RULES.contains(rule) {
+                issues.remove_by_rule(rule);
+            }
+        }
+    }
+
+    if args.with_lockfile && !issues.is_rule_ignored("lockfile-drift") {
+        match lockfile::LockfileVersions::load(&root) {
+            Ok(Some(lockfile)) => {
+                lockfile::check_drift(&catalogs, &lint_options, &lockfile, &mut issues)
+            }
+            Ok(None) => printer::print_warning(
+                &mut io::stderr(),
+                "--with-lockfile: no pnpm-lock.yaml found, skipping lockfile-drift",
+            ),
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+                process::exit(1);
+            }
+        }
+    }
+
+    if args.verify_registry && !issues.is_rule_ignored("registry-version-exists") {
+        let mut registry = registry::Registry::new(&args.registry, args.network_options());
+        for warning in
+            registry::verify_catalogs(&catalogs, &lint_options, &mut registry, &mut issues)
+        {
+            printer::print_warning(&mut io::stderr(), &warning);
+        }
+    }
+
+    if let Some(url) = &args.reference_catalog_url
+        && !issues.is_rule_ignored("reference-catalog-drift")
+    {
+        let cache_dir = root.join(reference::CACHE_DIR);
+        match reference::load_reference_catalog(url, &args.network_options(), &cache_dir) {
+            Ok((reference, warnings)) => {
+                for warning in warnings {
+                    printer::print_warning(&mut io::stderr(), &warning);
                 }
-                Err(e) => {
-                    printer::print_error(&format!("Failed to fix: {e:#}"));
+                if let Some(reference) = reference {
+                    reference::check_drift(&catalogs, &lint_options, &reference, &mut issues);
                 }
-            },
+            }
             Err(e) => {
-                printer::print_error(&format!("Failed to fix: {e:#}"));
+                printer::print_error(&mut io::stderr(), &format!("{e:#}"));
+                process::exit(1);
             }
         }
     }
 
-    if args.fix && !fix.version_replacements.is_empty() {
-        match packages::replace_versions(&fix.version_replacements) {
-            Ok(count) => {
-                if !is_quiet && !is_json {
-                    printer::print_fixed_versions(count);
+    // A single packed package can't tell whether a catalog entry is used elsewhere,
+    // or which workspace packages exist
+    if args.from_tarball.is_some() {
+        issues.remove_by_rule("unused-catalog-entry");
+        issues.remove_by_rule("dangling-workspace-ref");
+    }
+
+    // --patch-output runs the same fixes, but renders them as a diff instead of writing them
+    let patching = args.patch_output.is_some();
+    let fixing = args.fix || patching;
+    let mut edits = edits::FileEdits::default();
+    let mut steps = Vec::new();
+
+    if fixing && !fix.catalog_additions.is_empty() {
+        match workspace::add_catalog_entries(&mut edits, &root, &fix.catalog_additions) {
+            Ok(added) => {
+                match packages::replace_versions(&mut edits, &fix.catalog_addition_replacements) {
+                    Ok(replaced) => steps.push(FixStep::CatalogAdditions { added, replaced }),
+                    Err(e) => {
+                        printer::print_error(&mut io::stderr(), &format!("Failed to fix: {e:#}"));
+                    }
                 }
-                issues.remove_by_rule("no-direct-version");
             }
             Err(e) => {
-                printer::print_error(&format!("Failed to fix: {e:#}"));
+                printer::print_error(&mut io::stderr(), &format!("Failed to fix: {e:#}"));
             }
         }
     }
 
-    if args.fix && !fix.unused_entries.is_empty() {
-        match workspace::remove_catalog_entries(&root, &fix.unused_entries) {
-            Ok(count) => {
-                if !is_quiet && !is_json {
-                    printer::print_fixed(count);
+    if fixing && !fix.version_replacements.is_empty() {
+        match packages::replace_versions(&mut edits, &fix.version_replacements) {
+            Ok(count) => steps.push(FixStep::Versions(count)),
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("Failed to fix: {e:#}"));
+            }
+        }
+    }
+
+    if fixing && !fix.unused_entries.is_empty() {
+        match workspace::remove_catalog_entries(&mut edits, &root, &fix.unused_entries) {
+            Ok(count) => steps.push(FixStep::UnusedEntries(count)),
+            Err(e) => {
+                printer::print_error(&mut io::stderr(), &format!("Failed to fix: {e:#}"));
+            }
+        }
+    }
+
+    if let Some(patch_output) = &args.patch_output {
+        // The workspace is left untouched, so every issue is still reported
+        if let Err(e) = std::fs::write(patch_output, edits.unified_diff(&display_base)) {
+            printer::print_error(
+                &mut io::stderr(),
+                &format!("Failed to write patch to {patch_output}: {e}"),
+            );
+            process::exit(1);
+        }
+        steps.clear();
+    } else if let Err(e) = edits.write_all() {
+        // Some files may already be rewritten; nothing counts as fixed
+        printer::print_error(&mut io::stderr(), &format!("Failed to fix: {e:#}"));
+        process::exit(1);
+    }
+
+    // Only once every file is written do the fixed issues go away
+    let fixed_any = !steps.is_empty();
+    for step in &steps {
+        if !is_quiet && (is_text || args.summary_stderr) {
+            let _ = step.print(&mut summary_out(args.summary_stderr));
+        }
+        issues.remove_fixed(step.rule());
+    }
+
+    // Make sure the rewritten files still resolve every catalog: reference
+    let mut fix_broke_refs = false;
+    if fixed_any {
+        let (before, _, _) = collect::collect_issues(
+            &packages,
+            &catalogs,
+            &collect::LintOptions {
+                rule_filter: rules::Filter::Only(vec!["catalog-entry-exists".to_string()]),
+                ..lint_options.clone()
+            },
+        );
+        match collect::verify_catalog_refs(&root, &lint_options, &load_options) {
+            Ok(after) => {
+                let known: HashSet<(String, String)> = before
+                    .iter()
+                    .map(|(pkg, issue)| (pkg.to_string(), issue.message()))
+                    .collect();
+                for (pkg, issue) in after.iter() {
+                    if !known.contains(&(pkg.to_string(), issue.message())) {
+                        printer::print_error(
+                            &mut io::stderr(),
+                            &format!(
+                                "--fix introduced a broken catalog reference in {pkg}: {}",
+                                issue.message()
+                            ),
+                        );
+                        fix_broke_refs = true;
+                    }
                 }
-                issues.remove_by_rule("unused-catalog-entry");
             }
             Err(e) => {
-                printer::print_error(&format!("Failed to fix: {e:#}"));
+                printer::print_error(&mut io::stderr(), &format!("Failed to verify fix: {e:#}"));
+                fix_broke_refs = true;
             }
         }
     }
 
+    // JSON consumers always get the stats, so a clean run still reports its denominators
+    let stats = (args.stats || is_json).then(|| usage.summarize(&catalogs));
+
     let duration = start.elapsed();
 
-    if issues.is_empty() {
+    let clean = issues.is_empty() && !fix_broke_refs;
+    let below_coverage = args
+        .min_coverage
+        .map(|min| (usage.coverage().percent(), min))
+        .filter(|(coverage, min)| coverage < min);
+    if args.sort_by_blame {
+        // git wants paths relative to the repository it runs in, whatever --relative-to says
+        let git_paths: HashMap<packages::PackageType, PathBuf> = packages
+            .iter()
+            .map(|pkg| {
+                let dir = pkg.path.strip_prefix(&root).unwrap_or(&pkg.path);
+                (pkg.package_type.clone(), dir.join("package.json"))
+            })
+            .collect();
+        sort_by_last_commit(&mut issues, &root, &git_paths);
+    }
+    let catalog_path = match workspace_file {
+        Some(file) => Path::new(file)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(file)),
+        None => root.join("pnpm-workspace.yaml"),
+    };
+    let mut manifest_paths: HashMap<packages::PackageType, PathBuf> = packages
+        .iter()
+        .map(|pkg| {
+            let manifest = workspace::relative_to(&pkg.path.join("package.json"), &display_base);
+            (pkg.package_type.clone(), manifest)
+        })
+        .collect();
+    manifest_paths.insert(
+        packages::PackageType::Catalog,
+        workspace::relative_to(&catalog_path, &display_base),
+    );
+
+    let report = printer::Report {
+        issues: &issues,
+        totals: printer::Totals {
+            packages: packages.len(),
+            catalog_entries: catalogs.entries().count(),
+        },
+        duration,
+        stats: stats.as_deref(),
+        manifest_paths: &manifest_paths,
+        catalog_lines: catalogs.entry_lines(),
+        clean,
+        keep_order: args.sort_by_blame,
+        junit_group: args.junit_group,
+        json_shape: args.json_shape,
+        rule_summary: args.rule_summary,
+        summary: true,
+        pretty: !args.compact,
+        max_issues: args.max_issues,
+    };
+
+    if let Some(output) = &args.output {
+        // Keep ANSI escapes out of the file, then restore the console setting
+        colored::control::set_override(false);
+        let written = File::create(output).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            // The file always gets the full list
+            let report = printer::Report {
+                max_issues: None,
+                ..report
+            };
+            printer::print_report(&mut out, args.format, &report, true)?;
+            out.flush()
+        });
+        if !args.no_color {
+            colored::control::unset_override();
+        }
+        if let Err(e) = written {
+            printer::print_error(
+                &mut io::stderr(),
+                &format!("Failed to write report to {output}: {e}"),
+            );
+            process::exit(1);
+        }
+
         if !is_quiet {
-            if is_json {
-                printer::print_json(&issues, duration);
-            } else if !args.fix {
-                printer::print_success();
-            }
+            let mut out = summary_out(args.summary_stderr);
+            let _ = printer::print_summary(&mut out, &report, true);
+            let _ = printer::print_report_written(&mut out, output);
+        }
+    } else if !is_quiet {
+        // Machine formats only get the summary line when it goes to stderr
+        let summary_elsewhere = args.summary_stderr;
+        let report = printer::Report {
+            summary: !summary_elsewhere,
+            ..report
+        };
+        if streaming {
+            let _ = printer::print_stream_end(&mut io::stdout().lock(), &report, streamed);
+        } else {
+            let _ =
+                printer::print_report(&mut io::stdout().lock(), args.format, &report, !args.fix);
         }
+        if summary_elsewhere {
+            let _ = printer::print_summary(&mut io::stderr(), &report, !args.fix);
+        }
+    }
+
+    if let Some((coverage, min)) = below_coverage {
+        printer::print_error(
+            &mut io::stderr(),
+            &format!("Catalog coverage {coverage:.1}% is below --min-coverage {min}%"),
+        );
+    }
+
+    if clean && below_coverage.is_none() {
         process::exit(0);
     }
 
-    if !is_quiet {
-        if is_json {
-            printer::print_json(&issues, duration);
-        } else {
-            printer::print_issues(&issues);
-            printer::print_footer(&issues, duration);
+    if let Some(reason) = exit_reason(
+        &issues,
+        args.fail_on_warnings,
+        fix_broke_refs,
+        below_coverage,
+    ) {
+        if args.explain_exit && !is_quiet {
+            printer::print_exit_reason(&mut io::stderr(), 1, &reason);
         }
+        process::exit(1);
     }
+}
 
-    let has_errors = issues.errors_count() > 0;
-    let has_failing_warnings = args.fail_on_warnings && issues.warnings_count() > 0;
-    if has_errors || has_failing_warnings {
-        process::exit(1);
+/// Sort issues so packages whose manifest changed most recently come first.
+/// Uncommitted manifests count as the newest. Leaves the order alone if git fails.
+fn sort_by_last_commit(
+    issues: &mut rules::IssuesList,
+    root: &Path,
+    manifest_paths: &HashMap<packages::PackageType, PathBuf>,
+) {
+    let mut times: HashMap<packages::PackageType, Option<i64>> = HashMap::new();
+    for (package_type, _) in issues.iter() {
+        if times.contains_key(package_type) {
+            continue;
+        }
+        let path = match package_type {
+            packages::PackageType::Catalog => Path::new("pnpm-workspace.yaml"),
+            other => match manifest_paths.get(other) {
+                Some(path) => path.as_path(),
+                None => continue,
+            },
+        };
+        match git::last_commit_time(root, path) {
+            Ok(time) => {
+                times.insert(package_type.clone(), time);
+            }
+            Err(e) => {
+                printer::print_warning(
+                    &mut io::stderr(),
+                    &format!("--sort-by-blame: {e:#}; keeping the default order"),
+                );
+                return;
+            }
+        }
+    }
+
+    issues.sort_by_package(|package_type| {
+        // Uncommitted first, packages git wasn't asked about last
+        let time = match times.get(package_type) {
+            Some(time) => time.unwrap_or(i64::MAX),
+            None => i64::MIN,
+        };
+        std::cmp::Reverse(time)
+    });
+}
+
+/// Where the summary line and `--fix` messages go: stdout, or stderr with `--summary-stderr`.
+fn summary_out(to_stderr: bool) -> Box<dyn Write> {
+    if to_stderr {
+        Box::new(io::stderr())
+    } else {
+        Box::new(io::stdout())
+    }
+}
+
+/// Why the run fails, checked in order: errors, a broken --fix, catalog coverage under
+/// --min-coverage (as coverage and minimum), then --fail-on-warnings.
+/// None means the run passes despite its issues.
+fn exit_reason(
+    issues: &rules::IssuesList,
+    fail_on_warnings: bool,
+    fix_broke_refs: bool,
+    below_coverage: Option<(f64, f64)>,
+) -> Option<String> {
+    let errors = issues.errors_count();
+    let warnings = issues.warnings_count();
+    if errors > 0 {
+        Some(format!(
+            "{errors} error{} present",
+            if errors == 1 { "" } else { "s" }
+        ))
+    } else if fix_broke_refs {
+        Some("--fix introduced broken catalog references".to_string())
+    } else if let Some((coverage, min)) = below_coverage {
+        Some(format!(
+            "catalog coverage {coverage:.1}% is below --min-coverage {min}%"
+        ))
+    } else if fail_on_warnings && warnings > 0 {
+        Some(format!(
+            "{warnings} warning{} present with --fail-on-warnings",
+            if warnings == 1 { "" } else { "s" }
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packages::PackageType;
+    use crate::rules::unused_catalog_entry::UnusedCatalogEntryIssue;
+
+    #[test]
+    fn warnings_only_fail_with_fail_on_warnings() {
+        let mut issues = rules::IssuesList::new(rules::Filter::None);
+        issues.add(
+            PackageType::Catalog,
+            Box::new(UnusedCatalogEntryIssue {
+                dependency_name: "lodash".to_string(),
+                catalog_name: None,
+                version: "^4.17.21".to_string(),
+            }),
+        );
+
+        assert_eq!(exit_reason(&issues, false, false, None), None);
+        assert_eq!(
+            exit_reason(&issues, true, false, None).as_deref(),
+            Some("1 warning present with --fail-on-warnings")
+        );
+        assert_eq!(
+            exit_reason(&issues, true, true, None).as_deref(),
+            Some("--fix introduced broken catalog references")
+        );
+    }
+
+    #[test]
+    fn low_coverage_fails_without_issues() {
+        let issues = rules::IssuesList::new(rules::Filter::None);
+        assert_eq!(
+            exit_reason(&issues, false, false, Some((62.5, 80.0))).as_deref(),
+            Some("catalog coverage 62.5% is below --min-coverage 80%")
+        );
     }
 }
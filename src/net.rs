@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// A response status worth retrying, e.g. 503 or 429. Other statuses are handled by the caller.
+#[derive(Debug, thiserror::Error)]
+#[error("{url} returned HTTP {status}")]
+pub struct HttpStatus {
+    pub url: String,
+    pub status: u16,
+}
+
+/// Timeout and retry settings shared by every check that goes over the network.
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkOptions {
+    /// Per-request timeout, covering connect, send and read
+    pub timeout: Duration,
+    /// Extra attempts after a transient failure
+    pub retries: u32,
+    /// Wait before the first retry; doubled before each following one
+    pub backoff: Duration,
+}
+
+impl Default for NetworkOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            retries: DEFAULT_RETRIES,
+            backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+impl NetworkOptions {
+    /// An HTTP agent using the timeout. Non-2xx statuses are returned, not turned into errors.
+    pub fn agent(&self) -> ureq::Agent {
+        ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .http_status_as_error(false)
+            .build()
+            .into()
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error that isn't transient,
+    /// or the retries run out. The last error is returned.
+    pub fn retry<T>(&self, mut attempt: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut backoff = self.backoff;
+        let mut retries_left = self.retries;
+        loop {
+            match attempt() {
+                Err(e) if retries_left > 0 && is_transient(&e) => {
+                    retries_left -= 1;
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Connection failures, timeouts, 5xx and 429 responses may succeed on a later attempt.
+pub fn is_transient(e: &anyhow::Error) -> bool {
+    if let Some(status) = e.downcast_ref::<HttpStatus>() {
+        return status.status == 429 || status.status >= 500;
+    }
+    is_unreachable(e)
+}
+
+/// The request never got a response, e.g. the host is down or the timeout expired.
+pub fn is_unreachable(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<ureq::Error>()
+        .is_some_and(|e| !matches!(e, ureq::Error::StatusCode(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+
+    fn options(retries: u32) -> NetworkOptions {
+        NetworkOptions {
+            retries,
+            backoff: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    fn status(status: u16) -> anyhow::Error {
+        HttpStatus {
+            url: "http://registry.test/react".to_string(),
+            status,
+        }
+        .into()
+    }
+
+    #[test]
+    fn retries_transient_failures_until_success() {
+        let mut attempts = 0;
+        let result = options(2).retry(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(status(503))
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn gives_up_after_the_last_retry() {
+        let mut attempts = 0;
+        let result: Result<()> = options(1).retry(|| {
+            attempts += 1;
+            Err(status(429))
+        });
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "http://registry.test/react returned HTTP 429"
+        );
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn does_not_retry_permanent_failures() {
+        let mut attempts = 0;
+        let result: Result<()> = options(3).retry(|| {
+            attempts += 1;
+            bail!("Invalid registry response")
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        assert!(!is_transient(&status(403)));
+    }
+}
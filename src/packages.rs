@@ -1,17 +1,24 @@
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use indexmap::IndexMap;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::collect::VersionReplacement;
+use crate::edits::FileEdits;
+use crate::error::LintError;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PackageType {
+    /// The workspace root package.json
     Root,
     Workspace(String),
+    /// pnpm-workspace.yaml itself, for issues about catalog entries rather than a package
+    Catalog,
 }
 
 impl std::fmt::Display for PackageType {
@@ -19,11 +26,13 @@ impl std::fmt::Display for PackageType {
         match self {
             PackageType::Root => write!(f, "(root)"),
             PackageType::Workspace(name) => write!(f, "{name}"),
+            PackageType::Catalog => write!(f, "pnpm-workspace.yaml"),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum DependencyKind {
     Dependencies,
     DevDependencies,
@@ -49,9 +58,19 @@ pub struct Dependency {
     pub kind: DependencyKind,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct PackageJson {
     pub name: Option<String>,
+    /// Private packages are never published. Absent means public.
+    #[serde(default, deserialize_with = "deserialize_private")]
+    pub private: bool,
+    /// e.g. "pnpm@9.1.0"; only checked by `doctor`
+    #[serde(
+        rename = "packageManager",
+        default,
+        deserialize_with = "deserialize_package_manager"
+    )]
+    pub package_manager: Option<PackageManager>,
     #[serde(default)]
     pub dependencies: IndexMap<String, String>,
     #[serde(rename = "devDependencies", default)]
@@ -60,6 +79,203 @@ pub struct PackageJson {
     pub peer_dependencies: IndexMap<String, String>,
     #[serde(rename = "optionalDependencies", default)]
     pub optional_dependencies: IndexMap<String, String>,
+    /// Marks peers optional; values are objects, not versions, so they are never linted
+    #[allow(dead_code)]
+    #[serde(rename = "peerDependenciesMeta", default)]
+    pub peer_dependencies_meta: IndexMap<String, serde::de::IgnoredAny>,
+    /// npm/yarn-style package globs; only read from the root package.json as a fallback
+    /// for a pnpm-workspace.yaml without `packages`
+    #[serde(default, deserialize_with = "deserialize_workspaces")]
+    pub workspaces: Option<Workspaces>,
+    /// Package-local lint settings, on top of the workspace config
+    #[serde(
+        rename = "catalogLint",
+        default,
+        deserialize_with = "deserialize_catalog_lint"
+    )]
+    pub catalog_lint: PackageLintConfig,
+    /// Rules, policies and `dep:<name>` dependencies listed under the comment-style
+    /// `"//catalogLintIgnore"` key, which pnpm and npm skip like any `//` key
+    #[serde(
+        rename = "//catalogLintIgnore",
+        default,
+        deserialize_with = "deserialize_lenient_list"
+    )]
+    pub inline_ignores: Vec<String>,
+}
+
+/// A list of strings, or one string separated by commas or whitespace. Being a comment,
+/// anything else is read as empty rather than failing the package.
+fn deserialize_lenient_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let items = match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(list) => list
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::to_string)
+            .collect(),
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
+/// Only `true` makes a package private, as only `true` stops npm from publishing it; any
+/// other value is read as public rather than failing the package.
+fn deserialize_private<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(serde_json::Value::deserialize(deserializer)? == serde_json::Value::Bool(true))
+}
+
+/// A `packageManager` field that isn't a string is kept for `doctor` to report, rather than
+/// failing the package.
+fn deserialize_package_manager<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<PackageManager>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(spec) => Some(PackageManager::Spec(spec)),
+        other => Some(PackageManager::Invalid(other.to_string())),
+    })
+}
+
+/// A `workspaces` field of another shape is read as absent: only the root's is ever used,
+/// so it mustn't fail the other packages.
+fn deserialize_workspaces<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Workspaces>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// A `catalogLint` field that can't be read makes no exceptions, and is reported as an issue
+/// of the package instead of failing to load it.
+fn deserialize_catalog_lint<'de, D>(
+    deserializer: D,
+) -> std::result::Result<PackageLintConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(
+        serde_json::from_value(value).unwrap_or_else(|e| PackageLintConfig {
+            invalid: Some(e.to_string()),
+            ..Default::default()
+        }),
+    )
+}
+
+/// The `catalogLint` field: exceptions a package makes for itself.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PackageLintConfig {
+    /// Dependencies this package may pin directly, like `--allow-direct`
+    #[serde(default)]
+    pub allow_direct: Vec<String>,
+    /// Rules and policies not reported for this package
+    #[serde(default)]
+    pub ignore_rules: Vec<String>,
+    /// Dependencies not reported for this package; only set from `"//catalogLintIgnore"`
+    #[serde(skip)]
+    pub ignore_dependencies: Vec<String>,
+    /// Why the field couldn't be read, if it couldn't
+    #[serde(skip)]
+    pub invalid: Option<String>,
+    /// `"//catalogLintIgnore"` items that are neither a rule, a policy nor a `dep:` item
+    #[serde(skip)]
+    pub unknown_inline: Vec<String>,
+}
+
+impl PackageLintConfig {
+    /// Add the `"//catalogLintIgnore"` entries: rule and policy names, as told by `is_rule`,
+    /// to the ignored rules, `dep:<name>` items to the ignored dependencies. Anything else
+    /// is kept as unknown, so a misspelled rule isn't taken for a dependency.
+    pub fn with_inline_ignores(&self, inline: &[String], is_rule: impl Fn(&str) -> bool) -> Self {
+        let mut config = self.clone();
+        for item in inline {
+            if let Some(dependency) = item.strip_prefix("dep:") {
+                config.ignore_dependencies.push(dependency.to_string());
+            } else if is_rule(item) {
+                config.ignore_rules.push(item.clone());
+            } else {
+                config.unknown_inline.push(item.clone());
+            }
+        }
+        config
+    }
+
+    /// What's wrong with the exceptions: why `catalogLint` couldn't be read, `ignoreRules`
+    /// entries that aren't rules or policies, as told by `is_rule`, and unknown
+    /// `"//catalogLintIgnore"` items.
+    pub fn problems(&self, is_rule: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut problems: Vec<String> = self
+            .invalid
+            .iter()
+            .map(|e| format!("\"catalogLint\" is invalid and was ignored: {e}"))
+            .collect();
+        for rule in self.ignore_rules.iter().filter(|rule| !is_rule(rule)) {
+            problems.push(format!(
+                "\"catalogLint.ignoreRules\" lists '{rule}', which is not a rule or policy"
+            ));
+        }
+        for item in &self.unknown_inline {
+            problems.push(format!(
+                "\"//catalogLintIgnore\" lists '{item}', which is not a rule or policy; \
+                 write a dependency as 'dep:{item}'"
+            ));
+        }
+        problems
+    }
+
+    pub fn ignores_rule(&self, rule_name: &str) -> bool {
+        self.ignore_rules.iter().any(|rule| rule == rule_name)
+    }
+}
+
+/// The `packageManager` field.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PackageManager {
+    /// e.g. "pnpm@9.1.0"
+    Spec(String),
+    /// A value that isn't a string, as JSON
+    Invalid(String),
+}
+
+/// The `workspaces` field: a list of globs, or yarn's `{ "packages": [...] }` form.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Workspaces {
+    Globs(Vec<String>),
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+impl PackageJson {
+    /// Package globs from the `workspaces` field, empty when it's absent.
+    pub fn workspace_globs(&self) -> &[String] {
+        match &self.workspaces {
+            Some(Workspaces::Globs(globs)) | Some(Workspaces::Object { packages: globs }) => globs,
+            None => &[],
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -67,15 +283,22 @@ pub struct Package {
     pub path: PathBuf,
     pub package_type: PackageType,
     pub inner: PackageJson,
+    /// The `packages:` glob whose expansion found this package; None for the root and tarballs
+    pub pattern: Option<String>,
 }
 
 impl Package {
-    pub fn load(dir: &Path, is_root: bool) -> Result<Self> {
+    pub fn load(dir: &Path, is_root: bool) -> std::result::Result<Self, LintError> {
         let pkg_path = dir.join("package.json");
-        let content = std::fs::read_to_string(&pkg_path)
-            .with_context(|| format!("Failed to read {}", pkg_path.display()))?;
-        let inner: PackageJson = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", pkg_path.display()))?;
+        let content = std::fs::read_to_string(&pkg_path).map_err(|source| LintError::Io {
+            path: pkg_path.clone(),
+            source,
+        })?;
+        let inner: PackageJson =
+            serde_json::from_str(&content).map_err(|source| LintError::PackageParse {
+                path: pkg_path.clone(),
+                source,
+            })?;
 
         let package_type = if is_root {
             PackageType::Root
@@ -92,9 +315,53 @@ impl Package {
             path: dir.to_path_buf(),
             package_type,
             inner,
+            pattern: None,
         })
     }
 
+    /// Load the `package/package.json` bundled in an npm pack tarball (`.tgz`).
+    pub fn from_tarball(tarball: &Path) -> Result<Self> {
+        let file =
+            File::open(tarball).with_context(|| format!("Failed to open {}", tarball.display()))?;
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let entries = archive
+            .entries()
+            .with_context(|| format!("Failed to read tarball {}", tarball.display()))?;
+
+        for entry in entries {
+            let mut entry =
+                entry.with_context(|| format!("Failed to read tarball {}", tarball.display()))?;
+            if entry.path()?.as_ref() != Path::new("package/package.json") {
+                continue;
+            }
+
+            let mut content = String::new();
+            entry.read_to_string(&mut content).with_context(|| {
+                format!("Failed to read package.json from {}", tarball.display())
+            })?;
+            let inner: PackageJson = serde_json::from_str(&content).with_context(|| {
+                format!("Failed to parse package.json from {}", tarball.display())
+            })?;
+
+            let name = inner.name.clone().unwrap_or_else(|| {
+                tarball
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            });
+
+            return Ok(Self {
+                path: tarball.to_path_buf(),
+                package_type: PackageType::Workspace(name),
+                inner,
+                pattern: None,
+            });
+        }
+
+        bail!("No package/package.json found in {}", tarball.display())
+    }
+
     pub fn all_dependencies(&self) -> Vec<Dependency> {
         let mut deps = Vec::new();
         for (name, version) in &self.inner.dependencies {
@@ -129,32 +396,130 @@ impl Package {
     }
 }
 
+/// Protocols resolved outside the catalogs and registry ranges, matched as prefixes.
+const SPECIAL_PROTOCOLS: &[&str] = &[
+    "workspace:",
+    "link:",
+    "file:",
+    "git:",
+    "git+",
+    "http:",
+    "https:",
+    "jsr:",
+];
+
+/// How a version string in package.json is resolved, which decides the rules that apply to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionKind {
+    /// The catalog: protocol and the catalog it names; None = default catalog
+    Catalog(Option<String>),
+    /// One of `SPECIAL_PROTOCOLS`, e.g. "workspace:" for "workspace:*"
+    Special(&'static str),
+    /// A registry version or range, e.g. "^1.0.0"; also malformed catalog refs like "Catalog:"
+    Direct,
+}
+
+pub fn classify_version(version: &str) -> VersionKind {
+    if let Some(catalog_name) = parse_catalog_ref(version) {
+        return VersionKind::Catalog(catalog_name);
+    }
+    match SPECIAL_PROTOCOLS
+        .iter()
+        .find(|protocol| version.starts_with(*protocol))
+    {
+        Some(protocol) => VersionKind::Special(protocol),
+        None => VersionKind::Direct,
+    }
+}
+
+/// A version resolved by pnpm outside the registry, e.g. a git or tarball URL or `workspace:*`.
+/// Such versions aren't semver and are left alone by the rules about version ranges.
+pub fn is_special_protocol(version: &str) -> bool {
+    matches!(classify_version(version), VersionKind::Special(_))
+}
+
+/// Split an `npm:` alias into the published name and its version range.
+/// - "npm:react@^18.2.0" → ("react", "^18.2.0")
+/// - "npm:@types/react@^18.2.0" → ("@types/react", "^18.2.0")
+/// - "npm:react" → ("react", "") — no range installs the latest version
+pub fn parse_npm_alias(version: &str) -> Option<(&str, &str)> {
+    let spec = version.trim().strip_prefix("npm:")?;
+    let (name, range) = match spec.rfind('@').filter(|&at| at > 0) {
+        Some(at) => (&spec[..at], &spec[at + 1..]),
+        None => (spec, ""),
+    };
+    (!name.is_empty()).then_some((name, range))
+}
+
+/// The package name and version range a dependency actually installs: the target of an
+/// `npm:` alias, otherwise the dependency itself. A catalog key like `react-18` with
+/// `"npm:react@^18.2.0"` installs `react` at `^18.2.0`.
+pub fn alias_target<'a>(dep_name: &'a str, version: &'a str) -> (&'a str, &'a str) {
+    parse_npm_alias(version).unwrap_or((dep_name, version))
+}
+
 /// Returns true if the version string uses the catalog: protocol.
 pub fn is_catalog_ref(version: &str) -> bool {
     version == "catalog:" || version.starts_with("catalog:")
 }
 
-/// Returns true if the version string uses a special protocol that should be skipped.
-pub fn is_special_protocol(version: &str) -> bool {
-    version.starts_with("workspace:")
-        || version.starts_with("link:")
-        || version.starts_with("file:")
-        || version.starts_with("git:")
-        || version.starts_with("git+")
-        || version.starts_with("http:")
-        || version.starts_with("https:")
+/// Returns true if the version string uses the catalog: protocol with the wrong casing,
+/// e.g. "Catalog:" or "CATALOG:react16". pnpm only recognizes the lowercase form.
+pub fn is_miscased_catalog_ref(version: &str) -> bool {
+    version
+        .get(..8)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("catalog:"))
+        && !is_catalog_ref(version)
+}
+
+/// The well-formed reference for a catalog: reference pnpm won't resolve as written, i.e.
+/// one with the wrong casing, surrounding whitespace, or slashes around the catalog name:
+/// - "Catalog:react16" → "catalog:react16"
+/// - "catalog: " → "catalog:"
+/// - "catalog:/react16" / "catalog:react16/" → "catalog:react16"
+///
+/// None for well-formed references and for anything that isn't a catalog: reference.
+pub fn malformed_catalog_ref(version: &str) -> Option<String> {
+    let trimmed = version.trim();
+    let prefix = trimmed.get(..8)?;
+    if !prefix.eq_ignore_ascii_case("catalog:") {
+        return None;
+    }
+    let name = trimmed[8..].trim().trim_matches('/').trim();
+    let suggestion = format!("catalog:{name}");
+    (suggestion != version).then_some(suggestion)
+}
+
+/// Resolve the workspace package a workspace: reference points to.
+/// - "workspace:*" / "workspace:^1.0.0" → the dependency name itself
+/// - "workspace:@scope/pkg@*" → "@scope/pkg" (aliased)
+/// - "workspace:../pkg" → None — relative paths aren't resolved by name
+/// - "^1.0.0" → None — not a workspace ref
+pub fn workspace_ref_target<'a>(dep_name: &'a str, version: &'a str) -> Option<&'a str> {
+    let spec = version.strip_prefix("workspace:")?;
+    if spec.starts_with('.') || spec.starts_with('/') {
+        return None;
+    }
+    match spec.rfind('@') {
+        Some(at) if at > 0 => Some(&spec[..at]),
+        _ => Some(dep_name),
+    }
 }
 
 /// Parse a catalog: reference to extract the catalog name.
 /// - "catalog:" → Some(None) — default catalog
 /// - "catalog:default" → Some(None) — default catalog (explicit)
 /// - "catalog:react16" → Some(Some("react16"))
+/// - "catalog: " / "catalog:/react16" → None — malformed, see `malformed_catalog_ref`
 /// - "^1.0.0" → None — not a catalog ref
 pub fn parse_catalog_ref(version: &str) -> Option<Option<String>> {
     if version == "catalog:" {
         return Some(None);
     }
     let name = version.strip_prefix("catalog:")?;
+    if malformed_catalog_ref(version).is_some() {
+        return None;
+    }
     if name.is_empty() || name == "default" {
         Some(None)
     } else {
@@ -180,7 +545,10 @@ fn detect_indent(content: &str) -> String {
 
 /// Replace direct versions with catalog: references in package.json files.
 /// Returns the number of replacements made.
-pub fn replace_versions(replacements: &[VersionReplacement]) -> Result<usize> {
+pub fn replace_versions(
+    edits: &mut FileEdits,
+    replacements: &[VersionReplacement],
+) -> Result<usize> {
     let mut by_path: HashMap<&Path, Vec<&VersionReplacement>> = HashMap::new();
     for r in replacements {
         by_path.entry(&r.package_path).or_default().push(r);
@@ -190,8 +558,7 @@ pub fn replace_versions(replacements: &[VersionReplacement]) -> Result<usize> {
 
     for (dir, reps) in &by_path {
         let pkg_path = dir.join("package.json");
-        let content = std::fs::read_to_string(&pkg_path)
-            .with_context(|| format!("Failed to read {}", pkg_path.display()))?;
+        let content = edits.read(&pkg_path)?;
 
         let indent = detect_indent(&content);
         let has_trailing_newline = content.ends_with('\n');
@@ -228,8 +595,7 @@ pub fn replace_versions(replacements: &[VersionReplacement]) -> Result<usize> {
                 output.push('\n');
             }
 
-            std::fs::write(&pkg_path, &output)
-                .with_context(|| format!("Failed to write {}", pkg_path.display()))?;
+            edits.write(&pkg_path, output);
 
             total += count;
         }
@@ -242,6 +608,37 @@ pub fn replace_versions(replacements: &[VersionReplacement]) -> Result<usize> {
 mod tests {
     use super::*;
 
+    fn replace(replacements: &[VersionReplacement]) -> usize {
+        let mut edits = FileEdits::default();
+        let count = replace_versions(&mut edits, replacements).unwrap();
+        edits.write_all().unwrap();
+        count
+    }
+
+    #[test]
+    fn npm_alias_targets() {
+        assert_eq!(
+            parse_npm_alias("npm:react@^18.2.0"),
+            Some(("react", "^18.2.0"))
+        );
+        assert_eq!(
+            parse_npm_alias("npm:@types/react@^18.2.0"),
+            Some(("@types/react", "^18.2.0"))
+        );
+        assert_eq!(parse_npm_alias("npm:react"), Some(("react", "")));
+        assert_eq!(
+            parse_npm_alias("npm:@types/react"),
+            Some(("@types/react", ""))
+        );
+        assert_eq!(parse_npm_alias("npm:"), None);
+        assert_eq!(parse_npm_alias("^18.2.0"), None);
+        assert_eq!(
+            alias_target("react-18", "npm:react@^18.2.0"),
+            ("react", "^18.2.0")
+        );
+        assert_eq!(alias_target("react", "^18.2.0"), ("react", "^18.2.0"));
+    }
+
     #[test]
     fn test_is_catalog_ref() {
         assert!(is_catalog_ref("catalog:"));
@@ -253,7 +650,18 @@ mod tests {
     }
 
     #[test]
-    fn test_is_special_protocol() {
+    fn test_is_miscased_catalog_ref() {
+        assert!(is_miscased_catalog_ref("Catalog:"));
+        assert!(is_miscased_catalog_ref("CATALOG:"));
+        assert!(is_miscased_catalog_ref("cAtAlOg:react16"));
+        assert!(!is_miscased_catalog_ref("catalog:"));
+        assert!(!is_miscased_catalog_ref("catalog:react16"));
+        assert!(!is_miscased_catalog_ref("Catalog"));
+        assert!(!is_miscased_catalog_ref("^1.0.0"));
+    }
+
+    #[test]
+    fn test_special_protocols() {
         assert!(is_special_protocol("workspace:*"));
         assert!(is_special_protocol("workspace:^"));
         assert!(is_special_protocol("link:../utils"));
@@ -261,9 +669,84 @@ mod tests {
         assert!(is_special_protocol("git+https://github.com/foo/bar.git"));
         assert!(is_special_protocol("https://example.com/foo.tgz"));
         assert!(!is_special_protocol("^1.0.0"));
+        assert!(is_special_protocol("jsr:@std/path@^1.0.0"));
         assert!(!is_special_protocol("catalog:"));
     }
 
+    #[test]
+    fn test_classify_version() {
+        assert_eq!(classify_version("catalog:"), VersionKind::Catalog(None));
+        assert_eq!(
+            classify_version("catalog:default"),
+            VersionKind::Catalog(None)
+        );
+        assert_eq!(
+            classify_version("catalog:react16"),
+            VersionKind::Catalog(Some("react16".to_string()))
+        );
+        assert_eq!(
+            classify_version("workspace:*"),
+            VersionKind::Special("workspace:")
+        );
+        assert_eq!(
+            classify_version("jsr:@std/path@^1.0.0"),
+            VersionKind::Special("jsr:")
+        );
+        assert_eq!(classify_version("^1.0.0"), VersionKind::Direct);
+        assert_eq!(classify_version("Catalog:"), VersionKind::Direct);
+    }
+
+    #[test]
+    fn test_workspace_ref_target() {
+        assert_eq!(workspace_ref_target("utils", "workspace:*"), Some("utils"));
+        assert_eq!(
+            workspace_ref_target("utils", "workspace:^1.0.0"),
+            Some("utils")
+        );
+        assert_eq!(
+            workspace_ref_target("utils", "workspace:@acme/utils@*"),
+            Some("@acme/utils")
+        );
+        assert_eq!(
+            workspace_ref_target("@acme/utils", "workspace:^"),
+            Some("@acme/utils")
+        );
+        assert_eq!(workspace_ref_target("utils", "workspace:../utils"), None);
+        assert_eq!(workspace_ref_target("utils", "^1.0.0"), None);
+    }
+
+    #[test]
+    fn test_malformed_catalog_ref() {
+        let suggest = |version| malformed_catalog_ref(version);
+        assert_eq!(
+            suggest("Catalog:react16").as_deref(),
+            Some("catalog:react16")
+        );
+        assert_eq!(suggest("catalog: ").as_deref(), Some("catalog:"));
+        assert_eq!(
+            suggest(" catalog:react16 ").as_deref(),
+            Some("catalog:react16")
+        );
+        assert_eq!(
+            suggest("catalog: react16").as_deref(),
+            Some("catalog:react16")
+        );
+        assert_eq!(suggest("catalog:/react").as_deref(), Some("catalog:react"));
+        assert_eq!(
+            suggest("catalog:react16/").as_deref(),
+            Some("catalog:react16")
+        );
+        assert_eq!(suggest("catalog:/").as_deref(), Some("catalog:"));
+
+        assert_eq!(suggest("catalog:"), None);
+        assert_eq!(suggest("catalog:@acme/design"), None);
+        assert_eq!(suggest("^1.0.0"), None);
+        assert_eq!(suggest("catalogued"), None);
+
+        assert_eq!(parse_catalog_ref("catalog: "), None);
+        assert_eq!(parse_catalog_ref("catalog:/react"), None);
+    }
+
     #[test]
     fn test_parse_catalog_ref() {
         assert_eq!(parse_catalog_ref("catalog:"), Some(None));
@@ -272,6 +755,15 @@ mod tests {
             parse_catalog_ref("catalog:react16"),
             Some(Some("react16".to_string()))
         );
+        // Catalog names may look like scoped packages; everything after the prefix is the name
+        assert_eq!(
+            parse_catalog_ref("catalog:@acme/design"),
+            Some(Some("@acme/design".to_string()))
+        );
+        assert_eq!(
+            parse_catalog_ref("catalog:react-18.x"),
+            Some(Some("react-18.x".to_string()))
+        );
         assert_eq!(parse_catalog_ref("^1.0.0"), None);
         assert_eq!(parse_catalog_ref("workspace:*"), None);
     }
@@ -300,6 +792,133 @@ mod tests {
         assert_eq!(detect_indent(content), "  ");
     }
 
+    fn write_tarball(dir: &Path, files: &[(&str, &str)]) -> PathBuf {
+        let tarball = dir.join("app-1.0.0.tgz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&tarball).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        tarball
+    }
+
+    #[test]
+    fn from_tarball_reads_bundled_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let tarball = write_tarball(
+            dir.path(),
+            &[
+                ("package/README.md", "# app"),
+                (
+                    "package/package.json",
+                    r#"{ "name": "@acme/app", "dependencies": { "react": "catalog:" } }"#,
+                ),
+            ],
+        );
+
+        let pkg = Package::from_tarball(&tarball).unwrap();
+        assert_eq!(
+            pkg.package_type,
+            PackageType::Workspace("@acme/app".to_string())
+        );
+        assert_eq!(pkg.inner.dependencies["react"], "catalog:");
+    }
+
+    #[test]
+    fn inline_ignore_key_is_read_leniently() {
+        let inline = |json: &str| {
+            serde_json::from_str::<PackageJson>(json)
+                .unwrap()
+                .inline_ignores
+        };
+        assert_eq!(
+            inline(r#"{ "//catalogLintIgnore": ["no-direct-version", " lodash ", 3] }"#),
+            ["no-direct-version", "lodash"]
+        );
+        assert_eq!(
+            inline(r#"{ "//catalogLintIgnore": "no-direct-version, lodash react" }"#),
+            ["no-direct-version", "lodash", "react"]
+        );
+        assert!(inline(r#"{ "//catalogLintIgnore": { "rules": ["x"] } }"#).is_empty());
+        assert!(inline(r#"{ "//": "not ours" }"#).is_empty());
+    }
+
+    #[test]
+    fn private_is_read_leniently() {
+        let private = |json: &str| serde_json::from_str::<PackageJson>(json).unwrap().private;
+        assert!(private(r#"{ "private": true }"#));
+        assert!(!private(r#"{ "private": false }"#));
+        assert!(!private(r#"{ "private": "true" }"#));
+        assert!(!private(r#"{ "private": null }"#));
+        assert!(!private(r#"{ "name": "app" }"#));
+    }
+
+    #[test]
+    fn workspaces_of_another_shape_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("packages/app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(
+            app.join("package.json"),
+            r#"{ "name": "app", "workspaces": "packages/*", "dependencies": { "react": "catalog:" } }"#,
+        )
+        .unwrap();
+
+        let pkg = Package::load(&app, false).unwrap();
+        assert!(pkg.inner.workspace_globs().is_empty());
+        assert_eq!(pkg.inner.dependencies["react"], "catalog:");
+
+        let globs = |json: &str| {
+            serde_json::from_str::<PackageJson>(json)
+                .unwrap()
+                .workspace_globs()
+                .to_vec()
+        };
+        assert_eq!(globs(r#"{ "workspaces": ["apps/*"] }"#), ["apps/*"]);
+        assert_eq!(
+            globs(r#"{ "workspaces": { "packages": ["apps/*"] } }"#),
+            ["apps/*"]
+        );
+        assert!(globs(r#"{ "workspaces": 3 }"#).is_empty());
+    }
+
+    #[test]
+    fn package_manager_is_read_leniently() {
+        let package_manager = |json: &str| {
+            serde_json::from_str::<PackageJson>(json)
+                .unwrap()
+                .package_manager
+        };
+        assert_eq!(
+            package_manager(r#"{ "packageManager": "pnpm@9.1.0" }"#),
+            Some(PackageManager::Spec("pnpm@9.1.0".to_string()))
+        );
+        assert_eq!(
+            package_manager(r#"{ "packageManager": { "name": "pnpm" } }"#),
+            Some(PackageManager::Invalid(r#"{"name":"pnpm"}"#.to_string()))
+        );
+        assert_eq!(package_manager(r#"{ "name": "app" }"#), None);
+    }
+
+    #[test]
+    fn from_tarball_without_package_json_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let tarball = write_tarball(dir.path(), &[("package/README.md", "# app")]);
+
+        let err = Package::from_tarball(&tarball).unwrap_err();
+        assert!(err.to_string().contains("No package/package.json found"));
+    }
+
     #[test]
     fn replace_versions_default_catalog() {
         let dir = tempfile::tempdir().unwrap();
@@ -320,7 +939,7 @@ mod tests {
             catalog_ref: "catalog:".to_string(),
         }];
 
-        let count = replace_versions(&replacements).unwrap();
+        let count = replace(&replacements);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
@@ -342,7 +961,7 @@ mod tests {
             catalog_ref: "catalog:legacy".to_string(),
         }];
 
-        let count = replace_versions(&replacements).unwrap();
+        let count = replace(&replacements);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
@@ -370,7 +989,7 @@ mod tests {
             },
         ];
 
-        let count = replace_versions(&replacements).unwrap();
+        let count = replace(&replacements);
         assert_eq!(count, 2);
 
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
@@ -391,7 +1010,7 @@ mod tests {
             catalog_ref: "catalog:".to_string(),
         }];
 
-        let count = replace_versions(&replacements).unwrap();
+        let count = replace(&replacements);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
@@ -413,7 +1032,7 @@ mod tests {
             catalog_ref: "catalog:".to_string(),
         }];
 
-        replace_versions(&replacements).unwrap();
+        replace(&replacements);
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
         assert!(result.ends_with('\n'));
     }
@@ -432,7 +1051,7 @@ mod tests {
             catalog_ref: "catalog:".to_string(),
         }];
 
-        replace_versions(&replacements).unwrap();
+        replace(&replacements);
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
         assert!(!result.ends_with('\n'));
     }
@@ -450,7 +1069,7 @@ mod tests {
             catalog_ref: "catalog:".to_string(),
         }];
 
-        replace_versions(&replacements).unwrap();
+        replace(&replacements);
         let result = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
 
         // Verify key order is preserved (name before version before scripts before dependencies)
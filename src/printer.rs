@@ -1,89 +1,198 @@
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use colored::Colorize;
+use indexmap::IndexMap;
 use serde::Serialize;
 
-use crate::packages::PackageType;
-use crate::rules::{IssueLevel, IssuesList};
+use crate::args::{JsonShape, JunitGroup, OutputFormat};
+use crate::config::EffectiveConfig;
+use crate::doctor::{Check, CheckStatus};
+use crate::packages::{Package, PackageType};
+use crate::rules::{Issue, IssueLevel, IssuesList, catalog_name_of, issue_order};
+use crate::stats::{CatalogStats, EntryReport};
+use crate::workspace::{CatalogEntry, WorkspaceCatalogs, relative_to};
 
-pub fn print_issues(issues: &IssuesList) {
-    // Group issues by package
-    let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
-
-    for (pkg_type, issue) in issues.iter() {
-        let key = match pkg_type {
-            PackageType::Root => "pnpm-workspace.yaml".to_string(),
-            PackageType::Workspace(name) => name.clone(),
-        };
+pub fn print_issues(
+    out: &mut impl Write,
+    issues: &IssuesList,
+    keep_order: bool,
+    limit: Option<usize>,
+) -> io::Result<()> {
+    print_grouped(out, issues.iter(), keep_order, limit)
+}
 
-        let line = format!(
-            "  {}[{}] {}",
-            issue.level(),
-            issue.name().dimmed(),
-            issue.message(),
-        );
+/// Print issues grouped by package. Packages are sorted by name unless `keep_order` is set,
+/// in which case they appear in the order of their first issue. Within a package, issues
+/// follow `issue_order`. With a `limit`, printing stops after that many issues and a
+/// line tells how many more there are.
+pub fn print_grouped<'a>(
+    out: &mut impl Write,
+    issues: impl Iterator<Item = &'a (PackageType, Box<dyn Issue>)>,
+    keep_order: bool,
+    limit: Option<usize>,
+) -> io::Result<()> {
+    let mut grouped: IndexMap<String, Vec<&dyn Issue>> = IndexMap::new();
 
-        grouped.entry(key).or_default().push(line);
+    for (pkg_type, issue) in issues {
+        grouped
+            .entry(pkg_type.to_string())
+            .or_default()
+            .push(issue.as_ref());
+    }
+    if !keep_order {
+        grouped.sort_keys();
     }
 
-    for (pkg, lines) in &grouped {
-        println!("{}", pkg.bold());
-        for line in lines {
-            println!("{line}");
+    let total: usize = grouped.values().map(Vec::len).sum();
+    let mut budget = limit.unwrap_or(total);
+    for (pkg, pkg_issues) in &mut grouped {
+        if budget == 0 {
+            break;
+        }
+        pkg_issues.sort_by(|a, b| issue_order(*a, *b));
+        writeln!(out, "{}", pkg.bold())?;
+        for issue in pkg_issues.iter().take(budget) {
+            writeln!(
+                out,
+                "  {}[{}] {}",
+                issue.level(),
+                issue.name().dimmed(),
+                issue.message(),
+            )?;
         }
-        println!();
+        budget = budget.saturating_sub(pkg_issues.len());
+        writeln!(out)?;
+    }
+    let hidden = total.saturating_sub(limit.unwrap_or(total));
+    if hidden > 0 {
+        let word = if hidden == 1 { "issue" } else { "issues" };
+        writeln!(
+            out,
+            "{}\n",
+            format!("...and {hidden} more {word} (--max-issues)").dimmed()
+        )?;
+    }
+    Ok(())
+}
+
+/// Describe issues hidden by the rule/package/dependency filters, e.g. "3 suppressed by ignore rules".
+fn suppression_notes(issues: &IssuesList) -> Vec<String> {
+    let mut notes = Vec::new();
+    let suppressed = issues.suppressed_count();
+    if suppressed > 0 {
+        notes.push(format!("{suppressed} suppressed by ignore rules"));
+    }
+    let packages = issues.skipped_packages();
+    if packages > 0 {
+        let word = if packages == 1 { "package" } else { "packages" };
+        notes.push(format!("{packages} {word} skipped"));
+    }
+    let dependencies = issues.skipped_dependencies();
+    if dependencies > 0 {
+        let word = if dependencies == 1 {
+            "dependency"
+        } else {
+            "dependencies"
+        };
+        notes.push(format!("{dependencies} {word} skipped"));
+    }
+    notes
+}
+
+pub fn print_success(out: &mut impl Write, issues: &IssuesList) -> io::Result<()> {
+    let notes = suppression_notes(issues);
+    if notes.is_empty() {
+        writeln!(out, "{}", "No issues found.".green().bold())
+    } else {
+        writeln!(
+            out,
+            "{} {}",
+            "No issues found".green().bold(),
+            format!("({}).", notes.join(", ")).dimmed(),
+        )
     }
 }
 
-pub fn print_success() {
-    println!("{}", "No issues found.".green().bold());
+/// Console note after `--output` wrote the full report, so CI logs point at the artifact.
+pub fn print_report_written(out: &mut impl Write, path: &str) -> io::Result<()> {
+    writeln!(out, "{}", format!("Full report written to {path}").dimmed())
 }
 
-pub fn print_fixed(count: usize) {
+/// Note after `init` wrote the starter config.
+pub fn print_config_written(out: &mut impl Write, path: &Path) -> io::Result<()> {
+    writeln!(out, "Wrote {}", path.display())
+}
+
+pub fn print_fixed(out: &mut impl Write, count: usize) -> io::Result<()> {
     let word = if count == 1 { "entry" } else { "entries" };
-    println!(
+    writeln!(
+        out,
         "{}",
         format!("Fixed {count} unused catalog {word}.")
             .green()
             .bold(),
-    );
+    )
 }
 
-pub fn print_fixed_versions(count: usize) {
+pub fn print_fixed_versions(out: &mut impl Write, count: usize) -> io::Result<()> {
     let word = if count == 1 {
         "dependency"
     } else {
         "dependencies"
     };
-    println!(
+    writeln!(
+        out,
         "{}",
         format!("Fixed {count} {word} to use catalog: protocol.")
             .green()
             .bold(),
-    );
+    )
 }
 
-pub fn print_fixed_catalog_additions(added: usize, replaced: usize) {
+pub fn print_fixed_catalog_additions(
+    out: &mut impl Write,
+    added: usize,
+    replaced: usize,
+) -> io::Result<()> {
     let entry_word = if added == 1 { "entry" } else { "entries" };
     let dep_word = if replaced == 1 {
         "dependency"
     } else {
         "dependencies"
     };
-    println!(
+    writeln!(
+        out,
         "{}",
         format!("Added {added} catalog {entry_word}, fixed {replaced} {dep_word} to use catalog: protocol.")
             .green()
             .bold(),
-    );
+    )
 }
 
-pub fn print_error(message: &str) {
-    eprintln!("{} {message}", "error:".red().bold());
+/// Write errors are ignored: this is already the error path, with nowhere left to report them.
+pub fn print_error(out: &mut impl Write, message: &str) {
+    let _ = writeln!(out, "{} {message}", "error:".red().bold());
 }
 
-pub fn print_footer(issues: &IssuesList, duration: Duration) {
+/// Like `print_error`, write errors are ignored.
+pub fn print_warning(out: &mut impl Write, message: &str) {
+    let _ = writeln!(out, "{} {message}", "warning:".yellow().bold());
+}
+
+/// Like `print_error`, write errors are ignored.
+pub fn print_exit_reason(out: &mut impl Write, code: i32, reason: &str) {
+    let _ = writeln!(out, "Exiting {code}: {reason}");
+}
+
+pub fn print_footer(
+    out: &mut impl Write,
+    issues: &IssuesList,
+    duration: Duration,
+) -> io::Result<()> {
     let errors = issues.errors_count();
     let warnings = issues.warnings_count();
     let total = errors + warnings;
@@ -103,67 +212,1284 @@ pub fn print_footer(issues: &IssuesList, duration: Duration) {
         ));
     }
 
-    println!(
-        "Found {} ({}) in {ms}ms",
+    // No breakdown when nothing is left, e.g. a run failed only by a broken --fix
+    let breakdown = if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    };
+    writeln!(
+        out,
+        "Found {}{breakdown} in {ms}ms",
         format!("{total} issue{}", if total == 1 { "" } else { "s" }).bold(),
-        parts.join(", "),
-    );
+    )?;
+
+    let notes = suppression_notes(issues);
+    if !notes.is_empty() {
+        writeln!(out, "{}", format!("({})", notes.join(", ")).dimmed())?;
+    }
+    Ok(())
+}
+
+/// One line with the issue count of each rule, e.g. "By rule: no-direct-version: 12, unused-catalog-entry: 3".
+pub fn print_rule_summary(out: &mut impl Write, issues: &IssuesList) -> io::Result<()> {
+    let counts = issues.rule_counts();
+    if counts.is_empty() {
+        return Ok(());
+    }
+    let counts: Vec<String> = counts
+        .iter()
+        .map(|(rule, count)| format!("{rule}: {count}"))
+        .collect();
+    writeln!(out, "By rule: {}", counts.join(", "))
+}
+
+pub fn print_stats(out: &mut impl Write, stats: &[CatalogStats]) -> io::Result<()> {
+    let name_width = stats
+        .iter()
+        .map(|s| s.name.len())
+        .chain(std::iter::once("Catalog".len()))
+        .max()
+        .unwrap_or_default();
+
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "{:<name_width$}  {:>7}  {:>8}  {:>11}  {:>6}",
+            "Catalog", "Entries", "catalog:", "Direct only", "Unused"
+        )
+        .bold()
+    )?;
+    for s in stats {
+        writeln!(
+            out,
+            "{:<name_width$}  {:>7}  {:>8}  {:>11}  {:>6}",
+            s.name, s.entries, s.catalog_refs, s.direct_only, s.unused
+        )?;
+    }
+    writeln!(out)
+}
+
+/// Each loaded package with its directory relative to `base` and the `packages:` glob that found it.
+pub fn print_package_sources(
+    out: &mut impl Write,
+    packages: &[Package],
+    base: &Path,
+) -> io::Result<()> {
+    let rows: Vec<(String, String, &str)> = packages
+        .iter()
+        .map(|pkg| {
+            let dir = relative_to(&pkg.path, base).display().to_string();
+            let dir = if dir.is_empty() { ".".to_string() } else { dir };
+            let pattern = pkg.pattern.as_deref().unwrap_or("-");
+            (pkg.package_type.to_string(), dir, pattern)
+        })
+        .collect();
+    let name_width = rows
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .chain(std::iter::once("Package".len()))
+        .max()
+        .unwrap_or_default();
+    let dir_width = rows
+        .iter()
+        .map(|(_, dir, _)| dir.len())
+        .chain(std::iter::once("Directory".len()))
+        .max()
+        .unwrap_or_default();
+
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "{:<name_width$}  {:<dir_width$}  Pattern",
+            "Package", "Directory"
+        )
+        .bold()
+    )?;
+    for (name, dir, pattern) in &rows {
+        writeln!(out, "{name:<name_width$}  {dir:<dir_width$}  {pattern}")?;
+    }
+    writeln!(out)
+}
+
+pub fn print_catalog_report(out: &mut impl Write, report: &[EntryReport]) -> io::Result<()> {
+    let width = |header: &str, column: fn(&EntryReport) -> &str| {
+        report
+            .iter()
+            .map(|r| column(r).len())
+            .chain(std::iter::once(header.len()))
+            .max()
+            .unwrap_or_default()
+    };
+    let catalog_width = width("Catalog", |r| &r.catalog);
+    let dep_width = width("Dependency", |r| &r.dependency);
+    let version_width = width("Version", |r| &r.version);
+
+    writeln!(
+        out,
+        "{}",
+        format!(
+            "{:<catalog_width$}  {:<dep_width$}  {:<version_width$}  {:>8}  {:>6}",
+            "Catalog", "Dependency", "Version", "catalog:", "Direct"
+        )
+        .bold()
+    )?;
+    for r in report {
+        let row = format!(
+            "{:<catalog_width$}  {:<dep_width$}  {:<version_width$}  {:>8}  {:>6}",
+            r.catalog, r.dependency, r.version, r.catalog_packages, r.direct_packages
+        );
+        if r.catalog_packages == 0 && r.direct_packages == 0 {
+            writeln!(out, "{}", row.dimmed())?;
+        } else {
+            writeln!(out, "{row}")?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_catalog_report_json(
+    out: &mut impl Write,
+    report: &[EntryReport],
+    pretty: bool,
+) -> io::Result<()> {
+    #[derive(Serialize)]
+    struct JsonCatalogReport<'a> {
+        schema_version: u32,
+        entries: &'a [EntryReport],
+    }
+    write_json(
+        out,
+        &JsonCatalogReport {
+            schema_version: JSON_SCHEMA_VERSION,
+            entries: report,
+        },
+        pretty,
+    )
+}
+
+pub fn print_config(out: &mut impl Write, config: &EffectiveConfig) -> io::Result<()> {
+    let name_width = config
+        .options
+        .iter()
+        .map(|o| o.name.len())
+        .max()
+        .unwrap_or_default();
+
+    for option in &config.options {
+        writeln!(
+            out,
+            "{:<name_width$}  {}  {}",
+            option.name,
+            option.value,
+            format!("({})", option.source).dimmed(),
+        )?;
+    }
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} {}",
+        "Enabled rules:".bold(),
+        config.enabled_rules.join(", ")
+    )
+}
+
+pub fn print_config_json(
+    out: &mut impl Write,
+    config: &EffectiveConfig,
+    pretty: bool,
+) -> io::Result<()> {
+    write_json(out, config, pretty)
+}
+
+/// What a run would check, printed by `--dry-run` instead of linting.
+pub struct DryRun<'a> {
+    pub root: &'a Path,
+    pub packages: &'a [Package],
+    pub catalogs: &'a WorkspaceCatalogs,
+    pub config: &'a EffectiveConfig,
+    /// Package directories are shown relative to this
+    pub base: &'a Path,
+}
+
+impl DryRun<'_> {
+    /// Entry count of each catalog, default catalog first, then named catalogs in file order.
+    fn catalog_sizes(&self) -> Vec<(&str, usize)> {
+        std::iter::once(("default", self.catalogs.default().len()))
+            .chain(
+                self.catalogs
+                    .named()
+                    .iter()
+                    .map(|(name, entries)| (name.as_str(), entries.len())),
+            )
+            .collect()
+    }
+}
+
+pub fn print_dry_run(out: &mut impl Write, dry_run: &DryRun) -> io::Result<()> {
+    writeln!(out, "{} {}", "Root:".bold(), dry_run.root.display())?;
+    writeln!(out)?;
+    print_package_sources(out, dry_run.packages, dry_run.base)?;
+
+    let sizes = dry_run.catalog_sizes();
+    let name_width = sizes
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(std::iter::once("Catalog".len()))
+        .max()
+        .unwrap_or_default();
+    writeln!(
+        out,
+        "{}",
+        format!("{:<name_width$}  {:>7}", "Catalog", "Entries").bold()
+    )?;
+    for (name, entries) in &sizes {
+        writeln!(out, "{name:<name_width$}  {entries:>7}")?;
+    }
+    writeln!(out)?;
+
+    print_config(out, dry_run.config)?;
+    writeln!(out)?;
+    writeln!(out, "{}", "Dry run: no rules were run".dimmed())
+}
+
+#[derive(Serialize)]
+struct JsonDryRunPackage {
+    name: String,
+    directory: String,
+    pattern: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonDryRunCatalog<'a> {
+    name: &'a str,
+    entries: usize,
+}
+
+#[derive(Serialize)]
+struct JsonDryRun<'a> {
+    root: String,
+    packages: Vec<JsonDryRunPackage>,
+    catalogs: Vec<JsonDryRunCatalog<'a>>,
+    #[serde(flatten)]
+    config: &'a EffectiveConfig,
 }
 
+pub fn print_dry_run_json(out: &mut impl Write, dry_run: &DryRun, pretty: bool) -> io::Result<()> {
+    let packages = dry_run
+        .packages
+        .iter()
+        .map(|pkg| {
+            let dir = relative_to(&pkg.path, dry_run.base).display().to_string();
+            JsonDryRunPackage {
+                name: pkg.package_type.to_string(),
+                directory: if dir.is_empty() { ".".to_string() } else { dir },
+                pattern: pkg.pattern.clone(),
+            }
+        })
+        .collect();
+    let catalogs = dry_run
+        .catalog_sizes()
+        .into_iter()
+        .map(|(name, entries)| JsonDryRunCatalog { name, entries })
+        .collect();
+    let json = JsonDryRun {
+        root: dry_run.root.display().to_string(),
+        packages,
+        catalogs,
+        config: dry_run.config,
+    };
+    write_json(out, &json, pretty)
+}
+
+/// One JSON document per run: indented by default, on one line with `--compact`.
+fn write_json(out: &mut impl Write, value: &impl Serialize, pretty: bool) -> io::Result<()> {
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    writeln!(out, "{}", json.unwrap())
+}
+
+/// Version of the `--format json` shapes (the lint report and `catalog-report`),
+/// written as `schema_version`.
+/// Bump it when a field is removed, renamed or changes type or meaning; adding a field
+/// is not a breaking change and keeps the version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Serialize)]
 struct JsonIssue {
     package: String,
     level: &'static str,
     rule: String,
     message: String,
+    /// Why the rule exists
+    why: String,
+    /// Whether `--fix` resolves the issue
+    fixable: bool,
+    /// Line in pnpm-workspace.yaml of the catalog entry the issue is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
 }
 
 #[derive(Serialize)]
 struct JsonSummary {
+    /// Packages scanned, including the workspace root
+    packages: usize,
+    catalog_entries: usize,
     total: usize,
     errors: usize,
     warnings: usize,
+    /// Issues dropped by --exclude-rule/--only-rule
+    suppressed: usize,
+    skipped_packages: usize,
+    skipped_dependencies: usize,
     duration_ms: u128,
+    /// Issue count per rule or policy, most frequent first
+    rules: IndexMap<String, usize>,
 }
 
+/// The `issues` value, as selected by `--json-shape`
 #[derive(Serialize)]
-struct JsonOutput {
-    issues: Vec<JsonIssue>,
+#[serde(untagged)]
+enum JsonIssues {
+    Flat(Vec<JsonIssue>),
+    Grouped(IndexMap<String, Vec<JsonIssue>>),
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    schema_version: u32,
+    issues: JsonIssues,
     summary: JsonSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<&'a [CatalogStats]>,
 }
 
-pub fn print_json(issues: &IssuesList, duration: Duration) {
-    let json_issues: Vec<JsonIssue> = issues
-        .iter()
-        .map(|(pkg_type, issue)| {
-            let package = match pkg_type {
-                PackageType::Root => "pnpm-workspace.yaml".to_string(),
-                PackageType::Workspace(name) => name.clone(),
-            };
-            JsonIssue {
-                package,
-                level: match issue.level() {
-                    IssueLevel::Error => "error",
-                    IssueLevel::Warning => "warning",
-                },
-                rule: issue.name().to_string(),
-                message: issue.message(),
+/// Line of the catalog entry a catalog issue is about, if it names one.
+fn catalog_line(
+    catalog_lines: &HashMap<CatalogEntry, usize>,
+    pkg_type: &PackageType,
+    issue: &dyn Issue,
+) -> Option<usize> {
+    if *pkg_type != PackageType::Catalog {
+        return None;
+    }
+    let catalog_name = issue.catalog()?;
+    let entry = CatalogEntry {
+        catalog_name: catalog_name_of(catalog_name),
+        dependency_name: issue.dependency()?.to_string(),
+    };
+    catalog_lines.get(&entry).copied()
+}
+
+pub fn print_json(out: &mut impl Write, report: &Report) -> io::Result<()> {
+    let issues = report.issues;
+    let json_issue = |pkg_type: &PackageType, issue: &dyn Issue| JsonIssue {
+        package: pkg_type.to_string(),
+        level: match issue.level() {
+            IssueLevel::Error => "error",
+            IssueLevel::Warning => "warning",
+        },
+        rule: issue.name().to_string(),
+        message: issue.message(),
+        why: issue.why().to_string(),
+        fixable: issue.fixable(),
+        line: catalog_line(report.catalog_lines, pkg_type, issue),
+    };
+
+    let json_issues = match report.json_shape {
+        JsonShape::Flat => JsonIssues::Flat(
+            issues
+                .iter()
+                .map(|(pkg_type, issue)| json_issue(pkg_type, issue.as_ref()))
+                .collect(),
+        ),
+        // Same groups and order as the text report
+        JsonShape::Grouped => {
+            let mut grouped: IndexMap<String, Vec<(&PackageType, &dyn Issue)>> = IndexMap::new();
+            for (pkg_type, issue) in issues.iter() {
+                grouped
+                    .entry(pkg_type.to_string())
+                    .or_default()
+                    .push((pkg_type, issue.as_ref()));
             }
-        })
-        .collect();
+            if !report.keep_order {
+                grouped.sort_keys();
+            }
+            JsonIssues::Grouped(
+                grouped
+                    .into_iter()
+                    .map(|(pkg, mut pkg_issues)| {
+                        pkg_issues.sort_by(|(_, a), (_, b)| issue_order(*a, *b));
+                        let pkg_issues = pkg_issues
+                            .into_iter()
+                            .map(|(pkg_type, issue)| json_issue(pkg_type, issue))
+                            .collect();
+                        (pkg, pkg_issues)
+                    })
+                    .collect(),
+            )
+        }
+    };
 
     let errors = issues.errors_count();
     let warnings = issues.warnings_count();
 
     let output = JsonOutput {
+        schema_version: JSON_SCHEMA_VERSION,
         issues: json_issues,
         summary: JsonSummary {
+            packages: report.totals.packages,
+            catalog_entries: report.totals.catalog_entries,
             total: errors + warnings,
             errors,
             warnings,
-            duration_ms: duration.as_millis(),
+            suppressed: issues.suppressed_count(),
+            skipped_packages: issues.skipped_packages(),
+            skipped_dependencies: issues.skipped_dependencies(),
+            duration_ms: report.duration.as_millis(),
+            rules: issues
+                .rule_counts()
+                .into_iter()
+                .map(|(rule, count)| (rule.to_string(), count))
+                .collect(),
         },
+        stats: report.stats,
     };
 
-    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    write_json(out, &output, report.pretty)
+}
+
+pub fn print_checks(out: &mut impl Write, checks: &[Check]) -> io::Result<()> {
+    for check in checks {
+        let mark = match check.status {
+            CheckStatus::Pass => "pass".green().bold(),
+            CheckStatus::Warn => "warn".yellow().bold(),
+            CheckStatus::Fail => "fail".red().bold(),
+        };
+        writeln!(out, "{mark}  {}", check.message)?;
+    }
+    Ok(())
+}
+
+/// What a run covered, reported in JSON output whatever the outcome.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Totals {
+    pub packages: usize,
+    pub catalog_entries: usize,
+}
+
+/// Everything the report formats draw from.
+#[derive(Clone, Copy)]
+pub struct Report<'a> {
+    pub issues: &'a IssuesList,
+    pub totals: Totals,
+    pub duration: Duration,
+    pub stats: Option<&'a [CatalogStats]>,
+    /// Manifest path of each package, and pnpm-workspace.yaml for catalog issues,
+    /// relative to the workspace root or `--relative-to`
+    pub manifest_paths: &'a HashMap<PackageType, PathBuf>,
+    /// Line of each catalog entry in the manifest of `PackageType::Catalog`
+    pub catalog_lines: &'a HashMap<CatalogEntry, usize>,
+    /// Selects the success message instead of the issue list and footer
+    pub clean: bool,
+    /// List packages in issue order rather than by name (`--sort-by-blame`)
+    pub keep_order: bool,
+    pub junit_group: JunitGroup,
+    pub json_shape: JsonShape,
+    /// Add the per-rule issue counts to the text footer (`--rule-summary`)
+    pub rule_summary: bool,
+    /// End a text report with the summary line; false when it goes to stderr instead
+    /// (`--summary-stderr`)
+    pub summary: bool,
+    /// Indent JSON output; false with `--compact`
+    pub pretty: bool,
+    /// List at most this many issues in a text report (`--max-issues`); counts still
+    /// cover every issue
+    pub max_issues: Option<usize>,
+}
+
+/// Write the full lint report in the requested format.
+pub fn print_report(
+    out: &mut impl Write,
+    format: OutputFormat,
+    report: &Report,
+    show_success: bool,
+) -> io::Result<()> {
+    let Report {
+        issues,
+        totals: _,
+        duration,
+        stats,
+        manifest_paths,
+        catalog_lines: _,
+        clean,
+        keep_order,
+        junit_group,
+        json_shape: _,
+        rule_summary: _,
+        summary,
+        pretty: _,
+        max_issues,
+    } = *report;
+
+    match format {
+        OutputFormat::Json => print_json(out, report),
+        OutputFormat::Csv => print_delimited(out, issues, manifest_paths, ','),
+        OutputFormat::Tsv => print_delimited(out, issues, manifest_paths, '\t'),
+        OutputFormat::Junit => print_junit(out, issues, manifest_paths, duration, junit_group),
+        OutputFormat::Text => {
+            if !clean {
+                print_issues(out, issues, keep_order, max_issues)?;
+            }
+            if let Some(stats) = stats {
+                print_stats(out, stats)?;
+            }
+            if summary {
+                print_summary(out, report, show_success)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The human summary of a run: the success message, or the footer followed by the
+/// per-rule counts when `--rule-summary` is on.
+pub fn print_summary(out: &mut impl Write, report: &Report, show_success: bool) -> io::Result<()> {
+    if report.clean {
+        if show_success {
+            print_success(out, report.issues)?;
+        }
+        return Ok(());
+    }
+    print_footer(out, report.issues, report.duration)?;
+    if report.rule_summary {
+        print_rule_summary(out, report.issues)?;
+    }
+    Ok(())
+}
+
+/// One row per issue, with a header row, for spreadsheets.
+/// CSV fields are quoted when needed; TSV has no quoting, so tabs and newlines become spaces.
+pub fn print_delimited(
+    out: &mut impl Write,
+    issues: &IssuesList,
+    manifest_paths: &HashMap<PackageType, PathBuf>,
+    delimiter: char,
+) -> io::Result<()> {
+    let write_row = |out: &mut dyn Write, fields: [&str; 6]| -> io::Result<()> {
+        let fields: Vec<Cow<str>> = fields
+            .iter()
+            .map(|field| escape_field(field, delimiter))
+            .collect();
+        writeln!(out, "{}", fields.join(&delimiter.to_string()))
+    };
+
+    write_row(
+        out,
+        ["package", "path", "rule", "level", "dependency", "message"],
+    )?;
+    for (pkg_type, issue) in issues.iter() {
+        let path = manifest_paths
+            .get(pkg_type)
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let level = match issue.level() {
+            IssueLevel::Error => "error",
+            IssueLevel::Warning => "warning",
+        };
+        write_row(
+            out,
+            [
+                &pkg_type.to_string(),
+                &path,
+                issue.name(),
+                level,
+                issue.dependency().unwrap_or_default(),
+                &issue.message(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+struct JunitCase<'a> {
+    /// Manifest the issues are in, relative to the workspace root or `--relative-to`
+    path: String,
+    issues: Vec<&'a dyn Issue>,
+}
+
+/// JUnit XML. Each suite/test case pair gets one test case; all of its issues are listed
+/// in a single failure, typed "error" if any of them is an error.
+pub fn print_junit(
+    out: &mut impl Write,
+    issues: &IssuesList,
+    manifest_paths: &HashMap<PackageType, PathBuf>,
+    duration: Duration,
+    group: JunitGroup,
+) -> io::Result<()> {
+    let mut suites: IndexMap<String, IndexMap<String, JunitCase>> = IndexMap::new();
+    for (pkg_type, issue) in issues.iter() {
+        let package = pkg_type.to_string();
+        let (suite, case) = match group {
+            JunitGroup::Package => (package, issue.name().to_string()),
+            JunitGroup::Rule => (issue.name().to_string(), package),
+        };
+        let path = manifest_paths
+            .get(pkg_type)
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        suites
+            .entry(suite)
+            .or_default()
+            .entry(case)
+            .or_insert_with(|| JunitCase {
+                path,
+                issues: Vec::new(),
+            })
+            .issues
+            .push(issue.as_ref());
+    }
+    suites.sort_keys();
+
+    let total: usize = suites.values().map(|cases| cases.len()).sum();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="pnpm-catalog-lint" tests="{total}" failures="{total}" time="{:.3}">"#,
+        duration.as_secs_f64()
+    )?;
+    for (suite, cases) in &suites {
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{n}" failures="{n}">"#,
+            escape_xml(suite),
+            n = cases.len(),
+        )?;
+        for (
+            case,
+            JunitCase {
+                path,
+                issues: case_issues,
+            },
+        ) in cases
+        {
+            let level = if case_issues.iter().any(|i| i.level() == IssueLevel::Error) {
+                "error"
+            } else {
+                "warning"
+            };
+            let summary = match case_issues.as_slice() {
+                [issue] => issue.message(),
+                _ => format!("{} issues", case_issues.len()),
+            };
+            let body: Vec<String> = case_issues
+                .iter()
+                .map(|issue| {
+                    let level = match issue.level() {
+                        IssueLevel::Error => "error",
+                        IssueLevel::Warning => "warning",
+                    };
+                    format!("{level}[{}] {}", issue.name(), issue.message())
+                })
+                .collect();
+            writeln!(
+                out,
+                r#"    <testcase name="{}" classname="{}" file="{}">"#,
+                escape_xml(case),
+                escape_xml(suite),
+                escape_xml(path),
+            )?;
+            writeln!(
+                out,
+                r#"      <failure type="{level}" message="{}">{}</failure>"#,
+                escape_xml(&summary),
+                escape_xml(&body.join("\n")),
+            )?;
+            writeln!(out, "    </testcase>")?;
+        }
+        writeln!(out, "  </testsuite>")?;
+    }
+    writeln!(out, "</testsuites>")
+}
+
+fn escape_xml(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn escape_field(field: &str, delimiter: char) -> Cow<'_, str> {
+    if delimiter == '\t' {
+        if field.contains(['\t', '\n', '\r']) {
+            return Cow::Owned(field.replace(['\t', '\n', '\r'], " "));
+        }
+    } else if field.contains([delimiter, '"', '\n', '\r']) {
+        return Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")));
+    }
+    Cow::Borrowed(field)
+}
+
+/// Finish a `--stream` run: print the issues found after the first `streamed`
+/// (workspace-level ones), then stats and the footer.
+pub fn print_stream_end(out: &mut impl Write, report: &Report, streamed: usize) -> io::Result<()> {
+    print_grouped(out, report.issues.since(streamed).iter(), false, None)?;
+    if let Some(stats) = report.stats {
+        print_stats(out, stats)?;
+    }
+    if report.summary {
+        print_summary(out, report, true)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use crate::packages::DependencyKind;
+    use crate::rules::Filter;
+    use crate::rules::empty_catalog::EmptyCatalogIssue;
+    use crate::rules::no_direct_version::NoDirectVersionIssue;
+    use crate::rules::unused_catalog_entry::UnusedCatalogEntryIssue;
+
+    fn render(print: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        colored::control::set_override(false);
+        let mut out = Vec::new();
+        print(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn sample_issues(rule_filter: Filter) -> IssuesList {
+        let mut issues = IssuesList::new(rule_filter);
+        issues.add(
+            PackageType::Workspace("web".to_string()),
+            Box::new(NoDirectVersionIssue {
+                dependency_name: "react".to_string(),
+                version: "^18.2.0".to_string(),
+                kind: DependencyKind::Dependencies,
+                available_in: vec![None],
+            }),
+        );
+        issues.add(
+            PackageType::Catalog,
+            Box::new(UnusedCatalogEntryIssue {
+                dependency_name: "leftpad".to_string(),
+                catalog_name: None,
+                version: "^1.0.0".to_string(),
+            }),
+        );
+        issues
+    }
+
+    #[test]
+    fn issues_are_grouped_by_package() {
+        let issues = sample_issues(Filter::None);
+        let output = render(|out| print_issues(out, &issues, false, None));
+        assert_eq!(
+            output,
+            "pnpm-workspace.yaml\n  \
+             warning[unused-catalog-entry] 'leftpad' (\"^1.0.0\") in the default catalog is never referenced\n\
+             \n\
+             web\n  \
+             error[no-direct-version] 'react' uses \"^18.2.0\" in dependencies but is available in catalog: default. Use \"catalog:\" instead.\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn keep_order_lists_packages_by_first_issue() {
+        let issues = sample_issues(Filter::None);
+        let output = render(|out| print_issues(out, &issues, true, None));
+        let packages: Vec<&str> = output
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with(' '))
+            .collect();
+        assert_eq!(packages, vec!["web", "pnpm-workspace.yaml"]);
+    }
+
+    #[test]
+    fn footer_counts_issues_and_suppressions() {
+        let issues = sample_issues(Filter::None);
+        let output = render(|out| print_footer(out, &issues, Duration::from_millis(12)));
+        assert_eq!(output, "Found 2 issues (1 error, 1 warning) in 12ms\n");
+
+        let mut issues = sample_issues(Filter::Exclude(vec!["unused-catalog-entry".to_string()]));
+        issues.skip_package();
+        let output = render(|out| print_footer(out, &issues, Duration::from_millis(3)));
+        assert_eq!(
+            output,
+            "Found 1 issue (1 error) in 3ms\n(1 suppressed by ignore rules, 1 package skipped)\n"
+        );
+
+        let issues = sample_issues(Filter::Only(vec!["catalog-entry-exists".to_string()]));
+        let output = render(|out| print_footer(out, &issues, Duration::from_millis(1)));
+        assert_eq!(
+            output,
+            "Found 0 issues in 1ms\n(2 suppressed by ignore rules)\n"
+        );
+    }
+
+    #[test]
+    fn issues_within_a_package_are_ordered_by_level_rule_and_dependency() {
+        let unused = |dep: &str| -> Box<dyn Issue> {
+            Box::new(UnusedCatalogEntryIssue {
+                dependency_name: dep.to_string(),
+                catalog_name: None,
+                version: "^1.0.0".to_string(),
+            })
+        };
+        let mut issues = IssuesList::new(Filter::None);
+        issues.add(PackageType::Catalog, unused("zod"));
+        issues.add(
+            PackageType::Catalog,
+            Box::new(EmptyCatalogIssue {
+                catalog_name: "legacy".to_string(),
+            }),
+        );
+        issues.add(PackageType::Catalog, unused("axios"));
+        issues.add(
+            PackageType::Catalog,
+            Box::new(NoDirectVersionIssue {
+                dependency_name: "react".to_string(),
+                version: "^18.2.0".to_string(),
+                kind: DependencyKind::Dependencies,
+                available_in: vec![None],
+            }),
+        );
+
+        let output = render(|out| print_issues(out, &issues, false, None));
+        let rules: Vec<&str> = output
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once('[').map(|(_, rest)| rest))
+            .map(|rest| &rest[..rest.find(']').unwrap()])
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                "no-direct-version",
+                "empty-catalog",
+                "unused-catalog-entry",
+                "unused-catalog-entry"
+            ]
+        );
+        assert!(output.find("'axios'").unwrap() < output.find("'zod'").unwrap());
+    }
+
+    #[test]
+    fn package_sources_list_directory_and_pattern() {
+        let package = |dir: &str, package_type, pattern: Option<&str>| Package {
+            path: PathBuf::from("/repo").join(dir),
+            package_type,
+            inner: Default::default(),
+            pattern: pattern.map(str::to_string),
+        };
+        let packages = vec![
+            package("", PackageType::Root, None),
+            package(
+                "apps/web",
+                PackageType::Workspace("web".to_string()),
+                Some("apps/*"),
+            ),
+        ];
+
+        assert_eq!(
+            render(|out| print_package_sources(out, &packages, Path::new("/repo"))),
+            "Package  Directory  Pattern\n\
+             (root)   .          -\n\
+             web      apps/web   apps/*\n\n"
+        );
+    }
+
+    #[test]
+    fn dry_run_lists_packages_catalogs_and_options() {
+        let packages = vec![Package {
+            path: PathBuf::from("/repo/apps/web"),
+            package_type: PackageType::Workspace("web".to_string()),
+            inner: Default::default(),
+            pattern: Some("apps/*".to_string()),
+        }];
+        let mut named = IndexMap::new();
+        named.insert("legacy".to_string(), IndexMap::new());
+        let catalogs = WorkspaceCatalogs::new(
+            IndexMap::from([("react".to_string(), "^18.2.0".to_string())]),
+            named,
+        );
+        let config = EffectiveConfig {
+            options: Vec::new(),
+            enabled_rules: vec!["no-direct-version"],
+        };
+        let dry_run = DryRun {
+            root: Path::new("/repo"),
+            packages: &packages,
+            catalogs: &catalogs,
+            config: &config,
+            base: Path::new("/repo"),
+        };
+
+        assert_eq!(
+            render(|out| print_dry_run(out, &dry_run)),
+            "Root: /repo\n\
+             \n\
+             Package  Directory  Pattern\n\
+             web      apps/web   apps/*\n\
+             \n\
+             Catalog  Entries\n\
+             default        1\n\
+             legacy         0\n\
+             \n\
+             \n\
+             Enabled rules: no-direct-version\n\
+             \n\
+             Dry run: no rules were run\n"
+        );
+        assert_eq!(
+            render(|out| print_dry_run_json(out, &dry_run, false)),
+            "{\"root\":\"/repo\",\
+             \"packages\":[{\"name\":\"web\",\"directory\":\"apps/web\",\"pattern\":\"apps/*\"}],\
+             \"catalogs\":[{\"name\":\"default\",\"entries\":1},{\"name\":\"legacy\",\"entries\":0}],\
+             \"options\":[],\"enabled_rules\":[\"no-direct-version\"]}\n"
+        );
+    }
+
+    #[test]
+    fn rule_summary_counts_issues_per_rule() {
+        let mut issues = sample_issues(Filter::None);
+        issues.add(
+            PackageType::Catalog,
+            Box::new(UnusedCatalogEntryIssue {
+                dependency_name: "rimraf".to_string(),
+                catalog_name: None,
+                version: "^5.0.0".to_string(),
+            }),
+        );
+        assert_eq!(
+            render(|out| print_rule_summary(out, &issues)),
+            "By rule: unused-catalog-entry: 2, no-direct-version: 1\n"
+        );
+        assert_eq!(
+            render(|out| print_rule_summary(out, &IssuesList::new(Filter::None))),
+            ""
+        );
+    }
+
+    #[test]
+    fn success_mentions_suppressed_issues() {
+        let issues = IssuesList::new(Filter::None);
+        assert_eq!(
+            render(|out| print_success(out, &issues)),
+            "No issues found.\n"
+        );
+
+        let issues = sample_issues(Filter::Only(vec!["catalog-entry-exists".to_string()]));
+        assert_eq!(
+            render(|out| print_success(out, &issues)),
+            "No issues found (2 suppressed by ignore rules).\n"
+        );
+    }
+
+    #[test]
+    fn error_is_prefixed() {
+        let output = render(|out| {
+            print_error(out, "Invalid path 'nope'");
+            Ok(())
+        });
+        assert_eq!(output, "error: Invalid path 'nope'\n");
+    }
+
+    fn sample_stats() -> Vec<CatalogStats> {
+        vec![
+            CatalogStats {
+                name: "default".to_string(),
+                entries: 3,
+                catalog_refs: 1,
+                direct_only: 1,
+                unused: 1,
+            },
+            CatalogStats {
+                name: "legacy".to_string(),
+                entries: 1,
+                catalog_refs: 1,
+                direct_only: 0,
+                unused: 0,
+            },
+        ]
+    }
+
+    static NO_MANIFEST_PATHS: LazyLock<HashMap<PackageType, PathBuf>> = LazyLock::new(HashMap::new);
+    static NO_CATALOG_LINES: LazyLock<HashMap<CatalogEntry, usize>> = LazyLock::new(HashMap::new);
+
+    /// A report of `issues` with default settings, for tests to override what they check
+    fn sample_report(issues: &IssuesList) -> Report<'_> {
+        Report {
+            issues,
+            totals: Totals::default(),
+            duration: Duration::from_millis(7),
+            stats: None,
+            manifest_paths: &NO_MANIFEST_PATHS,
+            catalog_lines: &NO_CATALOG_LINES,
+            clean: issues.is_empty(),
+            keep_order: false,
+            junit_group: JunitGroup::Package,
+            json_shape: JsonShape::Flat,
+            rule_summary: false,
+            summary: true,
+            pretty: true,
+            max_issues: None,
+        }
+    }
+
+    fn report(format: OutputFormat, issues: &IssuesList, stats: Option<&[CatalogStats]>) -> String {
+        let manifest_paths = HashMap::from([
+            (
+                PackageType::Workspace("web".to_string()),
+                PathBuf::from("apps/web/package.json"),
+            ),
+            (PackageType::Catalog, PathBuf::from("pnpm-workspace.yaml")),
+        ]);
+        let report = Report {
+            totals: Totals {
+                packages: 2,
+                catalog_entries: 4,
+            },
+            stats,
+            manifest_paths: &manifest_paths,
+            ..sample_report(issues)
+        };
+        render(|out| print_report(out, format, &report, true))
+    }
+
+    #[test]
+    fn snapshot_text_report() {
+        let issues = sample_issues(Filter::None);
+        insta::assert_snapshot!(report(OutputFormat::Text, &issues, None));
+    }
+
+    #[test]
+    fn snapshot_text_report_with_stats() {
+        let mut issues = sample_issues(Filter::Exclude(vec!["unused-catalog-entry".to_string()]));
+        issues.skip_dependency();
+        let stats = sample_stats();
+        insta::assert_snapshot!(report(OutputFormat::Text, &issues, Some(&stats)));
+    }
+
+    #[test]
+    fn snapshot_text_report_clean() {
+        let issues = sample_issues(Filter::Only(vec!["catalog-entry-exists".to_string()]));
+        insta::assert_snapshot!(report(OutputFormat::Text, &issues, None));
+    }
+
+    #[test]
+    fn snapshot_json_report() {
+        let issues = sample_issues(Filter::None);
+        let stats = sample_stats();
+        insta::assert_snapshot!(report(OutputFormat::Json, &issues, Some(&stats)));
+    }
+
+    #[test]
+    fn snapshot_json_report_clean() {
+        let issues = sample_issues(Filter::Only(vec!["catalog-entry-exists".to_string()]));
+        let stats = sample_stats();
+        insta::assert_snapshot!(report(OutputFormat::Json, &issues, Some(&stats)));
+    }
+
+    #[test]
+    fn json_report_is_compact_without_pretty() {
+        let issues = sample_issues(Filter::None);
+        let report = Report {
+            pretty: false,
+            ..sample_report(&issues)
+        };
+        let output = render(|out| print_report(out, OutputFormat::Json, &report, true));
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.starts_with("{\"schema_version\":1,\"issues\":[{\"package\":\"web\""));
+    }
+
+    #[test]
+    fn json_issues_about_catalog_entries_have_their_line() {
+        let issues = sample_issues(Filter::None);
+        let catalog_lines = HashMap::from([
+            (
+                CatalogEntry {
+                    catalog_name: None,
+                    dependency_name: "leftpad".to_string(),
+                },
+                7,
+            ),
+            (
+                CatalogEntry {
+                    catalog_name: None,
+                    dependency_name: "react".to_string(),
+                },
+                3,
+            ),
+        ]);
+        let report = Report {
+            catalog_lines: &catalog_lines,
+            ..sample_report(&issues)
+        };
+        let output = render(|out| print_report(out, OutputFormat::Json, &report, true));
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        // react's issue is in web's package.json, not at its catalog entry
+        assert_eq!(json["issues"][0]["rule"], "no-direct-version");
+        assert!(json["issues"][0].get("line").is_none());
+        assert_eq!(json["issues"][1]["rule"], "unused-catalog-entry");
+        assert_eq!(json["issues"][1]["line"], 7);
+    }
+
+    #[test]
+    fn snapshot_json_report_grouped() {
+        let issues = sample_issues(Filter::None);
+        let report = Report {
+            json_shape: JsonShape::Grouped,
+            ..sample_report(&issues)
+        };
+        insta::assert_snapshot!(render(|out| print_report(
+            out,
+            OutputFormat::Json,
+            &report,
+            true
+        )));
+    }
+
+    #[test]
+    fn text_report_without_summary_leaves_the_footer_to_print_summary() {
+        let issues = sample_issues(Filter::None);
+        let report = Report {
+            rule_summary: true,
+            summary: false,
+            ..sample_report(&issues)
+        };
+        let output = render(|out| print_report(out, OutputFormat::Text, &report, true));
+        assert!(output.contains("[no-direct-version]"));
+        assert!(!output.contains("Found"));
+
+        assert_eq!(
+            render(|out| print_summary(out, &report, true)),
+            "Found 2 issues (1 error, 1 warning) in 7ms\n\
+             By rule: no-direct-version: 1, unused-catalog-entry: 1\n"
+        );
+    }
+
+    #[test]
+    fn max_issues_truncates_the_list_but_not_the_counts() {
+        let issues = sample_issues(Filter::None);
+        let report = Report {
+            max_issues: Some(1),
+            ..sample_report(&issues)
+        };
+        let output = render(|out| print_report(out, OutputFormat::Text, &report, true));
+        assert!(output.contains("[unused-catalog-entry]"));
+        assert!(!output.contains("[no-direct-version]"));
+        assert!(output.contains("...and 1 more issue (--max-issues)\n"));
+        assert!(output.ends_with("Found 2 issues (1 error, 1 warning) in 7ms\n"));
+
+        // Machine formats always list everything
+        let output = render(|out| print_report(out, OutputFormat::Csv, &report, true));
+        assert_eq!(output.lines().count(), 3);
+    }
+
+    #[test]
+    fn snapshot_csv_report() {
+        let issues = sample_issues(Filter::None);
+        insta::assert_snapshot!(report(OutputFormat::Csv, &issues, None));
+    }
+
+    #[test]
+    fn snapshot_tsv_report() {
+        let issues = sample_issues(Filter::None);
+        insta::assert_snapshot!(report(OutputFormat::Tsv, &issues, None));
+    }
+
+    #[test]
+    fn snapshot_junit_report() {
+        let issues = sample_issues(Filter::None);
+        insta::assert_snapshot!(report(OutputFormat::Junit, &issues, None));
+    }
+
+    #[test]
+    fn junit_grouped_by_rule_counts_packages_per_suite() {
+        let mut issues = sample_issues(Filter::None);
+        issues.add(
+            PackageType::Workspace("admin".to_string()),
+            Box::new(NoDirectVersionIssue {
+                dependency_name: "react".to_string(),
+                version: "^18.0.0".to_string(),
+                kind: DependencyKind::Dependencies,
+                available_in: vec![None],
+            }),
+        );
+        issues.add(
+            PackageType::Workspace("admin".to_string()),
+            Box::new(NoDirectVersionIssue {
+                dependency_name: "lodash".to_string(),
+                version: "^4.0.0".to_string(),
+                kind: DependencyKind::Dependencies,
+                available_in: vec![None],
+            }),
+        );
+        let output = render(|out| {
+            print_junit(
+                out,
+                &issues,
+                &HashMap::new(),
+                Duration::from_millis(7),
+                JunitGroup::Rule,
+            )
+        });
+
+        let suites: Vec<&str> = output
+            .lines()
+            .filter(|line| line.trim_start().starts_with("<testsuite "))
+            .map(str::trim)
+            .collect();
+        assert_eq!(
+            suites,
+            vec![
+                r#"<testsuite name="no-direct-version" tests="2" failures="2">"#,
+                r#"<testsuite name="unused-catalog-entry" tests="1" failures="1">"#,
+            ]
+        );
+        assert!(output.contains(r#"<failure type="error" message="2 issues">"#));
+    }
+
+    #[test]
+    fn xml_is_escaped() {
+        assert_eq!(escape_xml("plain"), "plain");
+        assert_eq!(
+            escape_xml(r#"'a' uses "<1" & more"#),
+            "&apos;a&apos; uses &quot;&lt;1&quot; &amp; more"
+        );
+    }
+
+    #[test]
+    fn delimited_fields_are_escaped() {
+        assert_eq!(escape_field("plain", ','), "plain");
+        assert_eq!(escape_field("a, b", ','), "\"a, b\"");
+        assert_eq!(
+            escape_field("uses \"^1.0.0\"", ','),
+            "\"uses \"\"^1.0.0\"\"\""
+        );
+        assert_eq!(escape_field("a, \"b\"", '\t'), "a, \"b\"");
+        assert_eq!(escape_field("a\tb\nc", '\t'), "a b c");
+    }
 }
@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::collect::LintOptions;
+use crate::net::{HttpStatus, NetworkOptions};
+use crate::packages::PackageType;
+use crate::rules::IssuesList;
+use crate::rules::reference_catalog_drift::ReferenceCatalogDriftIssue;
+use crate::workspace::{CatalogEntry, WorkspaceCatalogs, parse_workspace_str};
+
+/// Where fetched reference catalogs are kept for offline runs, under the workspace root.
+pub const CACHE_DIR: &str = "node_modules/.cache/pnpm-catalog-lint";
+
+/// Cache file for a reference catalog URL; one file per URL. The name is a 64-bit FNV-1a
+/// hash of the URL, which unlike std's hasher stays the same across Rust releases.
+fn cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    cache_dir.join(format!("reference-catalog-{hash:016x}.yaml"))
+}
+
+fn fetch(agent: &ureq::Agent, url: &str) -> Result<String> {
+    let mut response = agent
+        .get(url)
+        .call()
+        .with_context(|| format!("Failed to reach {url}"))?;
+    match response.status().as_u16() {
+        200 => response
+            .body_mut()
+            .read_to_string()
+            .with_context(|| format!("Failed to read the reference catalog from {url}")),
+        status => Err(HttpStatus {
+            url: url.to_string(),
+            status,
+        }
+        .into()),
+    }
+}
+
+/// Parse a reference catalog: `catalog` and `catalogs` keys like pnpm-workspace.yaml,
+/// written as YAML or JSON. Other keys are ignored.
+fn parse(content: &str, url: &str) -> Result<WorkspaceCatalogs> {
+    let (_, catalogs) = parse_workspace_str(content, Path::new(url))?;
+    Ok(catalogs)
+}
+
+/// Fetch the reference catalog at `url` and cache it in `cache_dir`. When the fetch fails,
+/// the copy cached by the last successful run is used instead; without one the check is
+/// skipped. Either way the run goes on, with the reasons returned as warnings.
+/// A reference catalog that can't be parsed is an error.
+pub fn load_reference_catalog(
+    url: &str,
+    network: &NetworkOptions,
+    cache_dir: &Path,
+) -> Result<(Option<WorkspaceCatalogs>, Vec<String>)> {
+    let cache = cache_path(cache_dir, url);
+    let agent = network.agent();
+    let mut warnings = Vec::new();
+
+    match network.retry(|| fetch(&agent, url)) {
+        Ok(content) => {
+            let catalogs = parse(&content, url)?;
+            let cached =
+                std::fs::create_dir_all(cache_dir).and_then(|()| std::fs::write(&cache, &content));
+            if let Err(e) = cached {
+                warnings.push(format!(
+                    "Failed to cache the reference catalog in {}: {e}",
+                    cache.display()
+                ));
+            }
+            Ok((Some(catalogs), warnings))
+        }
+        Err(e) => {
+            warnings.push(format!("{e:#}"));
+            match std::fs::read_to_string(&cache) {
+                Ok(content) => {
+                    warnings.push(format!(
+                        "Using the reference catalog cached in {}",
+                        cache.display()
+                    ));
+                    Ok((Some(parse(&content, url)?), warnings))
+                }
+                Err(_) => {
+                    warnings.push(
+                        "No cached reference catalog, skipping reference-catalog-drift".to_string(),
+                    );
+                    Ok((None, warnings))
+                }
+            }
+        }
+    }
+}
+
+/// Warn about catalog entries whose version differs from the same entry in the reference
+/// catalog, or from every version it lists. Entries the reference catalog doesn't have are
+/// local additions and are skipped.
+pub fn check_drift(
+    catalogs: &WorkspaceCatalogs,
+    options: &LintOptions,
+    reference: &WorkspaceCatalogs,
+    issues: &mut IssuesList,
+) {
+    for (catalog_name, dependency_name, version) in catalogs.entries() {
+        if !options.is_catalog_selected(catalog_name.map(String::as_str)) {
+            continue;
+        }
+        let entry = CatalogEntry {
+            catalog_name: catalog_name.cloned(),
+            dependency_name: dependency_name.clone(),
+        };
+        if reference
+            .accepted_versions(&entry)
+            .contains(&version.as_str())
+        {
+            continue;
+        }
+        let Some(reference_version) = reference.display_version(&entry) else {
+            continue;
+        };
+
+        issues.add(
+            PackageType::Catalog,
+            Box::new(ReferenceCatalogDriftIssue {
+                dependency_name: dependency_name.clone(),
+                catalog_name: catalog_name.cloned(),
+                version: version.clone(),
+                reference_version,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Filter;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    const REFERENCE: &str = "catalog:\n  react: ^18.3.1\n  lodash: ^4.17.21\n\
+                             catalogs:\n  legacy:\n    react: [^16.14.0, ^16.8.0]\n";
+
+    /// Serve `body` with a 200 on a local port, to every request.
+    fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/catalog.yaml", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        url
+    }
+
+    /// A URL nothing listens on.
+    fn unreachable_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/catalog.yaml", listener.local_addr().unwrap());
+        drop(listener);
+        url
+    }
+
+    fn no_retries() -> NetworkOptions {
+        NetworkOptions {
+            timeout: Duration::from_secs(2),
+            retries: 0,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn reports_entries_that_differ_from_the_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        let (reference, warnings) =
+            load_reference_catalog(&serve(REFERENCE), &no_retries(), dir.path()).unwrap();
+        assert!(warnings.is_empty());
+
+        let local = parse(
+            "catalog:\n  react: ^18.2.0\n  lodash: ^4.17.21\n  zod: ^3.23.0\n\
+             catalogs:\n  legacy:\n    react: ^16.8.0\n",
+            "pnpm-workspace.yaml",
+        )
+        .unwrap();
+        let mut issues = IssuesList::new(Filter::None);
+        check_drift(
+            &local,
+            &LintOptions::default(),
+            &reference.unwrap(),
+            &mut issues,
+        );
+
+        let messages: Vec<String> = issues.iter().map(|(_, issue)| issue.message()).collect();
+        assert_eq!(
+            messages,
+            [
+                "'react' is \"^18.2.0\" in the default catalog but \"^18.3.1\" in the reference catalog"
+            ]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_cache_when_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = unreachable_url();
+
+        let (reference, warnings) =
+            load_reference_catalog(&url, &no_retries(), dir.path()).unwrap();
+        assert!(reference.is_none());
+        assert_eq!(
+            warnings.last().unwrap(),
+            "No cached reference catalog, skipping reference-catalog-drift"
+        );
+
+        std::fs::write(cache_path(dir.path(), &url), REFERENCE).unwrap();
+        let (reference, warnings) =
+            load_reference_catalog(&url, &no_retries(), dir.path()).unwrap();
+        assert!(reference.unwrap().has_default_entry("lodash"));
+        assert!(
+            warnings
+                .last()
+                .unwrap()
+                .starts_with("Using the reference catalog cached in")
+        );
+    }
+
+    #[test]
+    fn cache_file_name_is_stable() {
+        assert_eq!(
+            cache_path(Path::new("cache"), "https://example.com/catalog.yaml"),
+            Path::new("cache/reference-catalog-a38d4825e8a90fe0.yaml")
+        );
+    }
+
+    #[test]
+    fn successful_fetch_is_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("node_modules/.cache/pnpm-catalog-lint");
+        let url = serve(REFERENCE);
+
+        load_reference_catalog(&url, &no_retries(), &cache_dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(cache_path(&cache_dir, &url)).unwrap(),
+            REFERENCE
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use nodejs_semver::{Range, Version};
+use serde::Deserialize;
+
+use crate::collect::LintOptions;
+use crate::net::{HttpStatus, NetworkOptions, is_unreachable};
+use crate::packages::{PackageType, alias_target};
+use crate::rules::IssuesList;
+use crate::rules::registry_version_exists::{RegistryMismatch, RegistryVersionExistsIssue};
+use crate::workspace::WorkspaceCatalogs;
+
+pub const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+
+/// The part of a registry packument we need. The abbreviated ("corgi") format is enough.
+#[derive(Deserialize)]
+struct Packument {
+    versions: HashMap<String, serde::de::IgnoredAny>,
+}
+
+/// Outcome of looking up a package; failures other than "not found" are errors.
+pub enum Lookup {
+    Published(Vec<Version>),
+    NotFound,
+}
+
+/// Blocking npm registry client. Every package is fetched at most once per run;
+/// transient failures are retried as configured by `NetworkOptions`.
+pub struct Registry {
+    base_url: String,
+    agent: ureq::Agent,
+    network: NetworkOptions,
+    cache: HashMap<String, Lookup>,
+}
+
+impl Registry {
+    pub fn new(base_url: &str, network: NetworkOptions) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent: network.agent(),
+            network,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn lookup(&mut self, name: &str) -> Result<&Lookup> {
+        if !self.cache.contains_key(name) {
+            let lookup = self.network.retry(|| self.fetch(name))?;
+            self.cache.insert(name.to_string(), lookup);
+        }
+        Ok(&self.cache[name])
+    }
+
+    fn fetch(&self, name: &str) -> Result<Lookup> {
+        // Scoped names keep their "@" but the slash must be escaped
+        let url = format!("{}/{}", self.base_url, name.replace('/', "%2f"));
+        let mut response = self
+            .agent
+            .get(&url)
+            .header(
+                "Accept",
+                "application/vnd.npm.install-v1+json; q=1.0, application/json; q=0.8",
+            )
+            .call()
+            .with_context(|| format!("Failed to reach {url}"))?;
+
+        match response.status().as_u16() {
+            404 => Ok(Lookup::NotFound),
+            200 => {
+                let packument: Packument = response
+                    .body_mut()
+                    .read_json()
+                    .with_context(|| format!("Invalid registry response for '{name}'"))?;
+                let versions = packument
+                    .versions
+                    .keys()
+                    .filter_map(|v| Version::parse(v).ok())
+                    .collect();
+                Ok(Lookup::Published(versions))
+            }
+            status => Err(HttpStatus { url, status }.into()),
+        }
+    }
+}
+
+/// Check that every catalog entry's version range matches at least one published version.
+/// `npm:` aliases are checked against the aliased package. Entries whose version isn't a
+/// semver range (dist-tags, URLs) are skipped.
+///
+/// Lookup failures never abort the run: once retries are exhausted they're returned as
+/// warnings, and if the registry is unreachable the remaining entries are skipped.
+pub fn verify_catalogs(
+    catalogs: &WorkspaceCatalogs,
+    options: &LintOptions,
+    registry: &mut Registry,
+    issues: &mut IssuesList,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for (catalog_name, dependency_name, version) in catalogs.entries() {
+        if !options.is_catalog_selected(catalog_name.map(String::as_str)) {
+            continue;
+        }
+        let (published_name, range) = alias_target(dependency_name, version);
+        let Ok(range) = Range::parse(range) else {
+            continue;
+        };
+
+        let mismatch = match registry.lookup(published_name) {
+            Ok(Lookup::NotFound) => RegistryMismatch::NotPublished,
+            Ok(Lookup::Published(versions)) => {
+                if versions.iter().any(|v| v.satisfies(&range)) {
+                    continue;
+                }
+                RegistryMismatch::NoMatchingVersion
+            }
+            Err(e) => {
+                let unreachable = is_unreachable(&e);
+                warnings.push(format!("{e:#}"));
+                if unreachable {
+                    warnings.push("Skipping remaining registry checks".to_string());
+                    break;
+                }
+                continue;
+            }
+        };
+
+        issues.add(
+            PackageType::Catalog,
+            Box::new(RegistryVersionExistsIssue {
+                dependency_name: dependency_name.clone(),
+                catalog_name: catalog_name.cloned(),
+                version: version.clone(),
+                mismatch,
+            }),
+        );
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Filter;
+    use indexmap::IndexMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Serve canned packuments on a local port. Unknown packages get a 404.
+    fn serve(packuments: &'static [(&'static str, &'static str)]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                reader.read_line(&mut request_line).unwrap();
+                // Drain headers
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap();
+                let response = match packuments
+                    .iter()
+                    .find(|(name, _)| path == format!("/{name}"))
+                {
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        url
+    }
+
+    fn no_backoff() -> NetworkOptions {
+        NetworkOptions {
+            backoff: std::time::Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    fn catalogs(entries: &[(&str, &str)]) -> WorkspaceCatalogs {
+        let mut default = IndexMap::new();
+        for (name, version) in entries {
+            default.insert(name.to_string(), version.to_string());
+        }
+        WorkspaceCatalogs::new(default, IndexMap::new())
+    }
+
+    #[test]
+    fn reports_unpublished_packages_and_unmatched_ranges() {
+        let url = serve(&[
+            ("react", r#"{ "versions": { "18.2.0": {}, "18.3.1": {} } }"#),
+            ("@acme%2fui", r#"{ "versions": { "1.0.0": {} } }"#),
+        ]);
+        let catalogs = catalogs(&[
+            ("react", "^18.2.0"),
+            ("react-18", "npm:react@^18.3.0"),
+            ("react-17", "npm:react@^17.0.0"),
+            ("@acme/ui", "^2.0.0"),
+            ("leftpad-typo", "^1.0.0"),
+            ("vite", "latest"),
+        ]);
+
+        let mut issues = IssuesList::new(Filter::None);
+        let warnings = verify_catalogs(
+            &catalogs,
+            &LintOptions::default(),
+            &mut Registry::new(&url, no_backoff()),
+            &mut issues,
+        );
+
+        assert!(warnings.is_empty());
+        let messages: Vec<String> = issues.iter().map(|(_, i)| i.message()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "'react-17' (\"npm:react@^17.0.0\") in the default catalog matches no version published to the registry",
+                "'@acme/ui' (\"^2.0.0\") in the default catalog matches no version published to the registry",
+                "'leftpad-typo' in the default catalog is not published to the registry",
+            ]
+        );
+    }
+
+    #[test]
+    fn unreachable_registry_warns_once() {
+        // Bind and drop to get a port nothing listens on
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let catalogs = catalogs(&[("react", "^18.2.0"), ("lodash", "^4.17.21")]);
+
+        let mut issues = IssuesList::new(Filter::None);
+        let warnings = verify_catalogs(
+            &catalogs,
+            &LintOptions::default(),
+            &mut Registry::new(&format!("http://127.0.0.1:{port}"), no_backoff()),
+            &mut issues,
+        );
+
+        assert!(issues.is_empty());
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1], "Skipping remaining registry checks");
+    }
+}
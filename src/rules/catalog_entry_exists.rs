@@ -1,5 +1,5 @@
 use crate::packages::DependencyKind;
-use crate::rules::{Issue, IssueLevel};
+use crate::rules::{Issue, IssueLevel, catalog_id};
 
 #[derive(Debug)]
 pub enum MissingCatalog {
@@ -7,8 +7,14 @@ pub enum MissingCatalog {
     DefaultEntry,
     /// "catalog:<name>" used but named catalog doesn't exist
     NamedCatalog(String),
-    /// "catalog:<name>" used, catalog exists, but dependency not in it
-    NamedEntry(String),
+    /// "catalog:<name>" used, catalog exists, but dependency not in it.
+    /// `in_default` is set when the default catalog does define the dependency,
+    /// `catalog_empty` when the named catalog has no entries at all.
+    NamedEntry {
+        catalog: String,
+        in_default: bool,
+        catalog_empty: bool,
+    },
 }
 
 pub struct CatalogEntryExistsIssue {
@@ -42,11 +48,29 @@ impl Issue for CatalogEntryExistsIssue {
                     self.dependency_name, self.kind,
                 )
             }
-            MissingCatalog::NamedEntry(name) => {
-                format!(
-                    "'{}' references \"catalog:{name}\" in {} but is not defined in catalog \"{name}\"",
-                    self.dependency_name, self.kind,
-                )
+            MissingCatalog::NamedEntry {
+                catalog: name,
+                in_default,
+                catalog_empty,
+            } => {
+                let mut message = if *catalog_empty {
+                    format!(
+                        "'{}' references \"catalog:{name}\" in {} but catalog \"{name}\" is empty",
+                        self.dependency_name, self.kind,
+                    )
+                } else {
+                    format!(
+                        "'{}' references \"catalog:{name}\" in {} but is not defined in catalog \"{name}\"",
+                        self.dependency_name, self.kind,
+                    )
+                };
+                if *in_default {
+                    message.push_str(&format!(
+                        ", but '{}' IS available in the default catalog — did you mean \"catalog:\"?",
+                        self.dependency_name,
+                    ));
+                }
+                message
             }
         }
     }
@@ -54,4 +78,20 @@ impl Issue for CatalogEntryExistsIssue {
     fn why(&self) -> &str {
         "A catalog: reference must point to an existing entry in pnpm-workspace.yaml. Missing entries will cause pnpm install to fail."
     }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        match &self.missing {
+            MissingCatalog::DefaultEntry => Some(catalog_id(None)),
+            MissingCatalog::NamedCatalog(name)
+            | MissingCatalog::NamedEntry { catalog: name, .. } => Some(name.clone()),
+        }
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
 }
@@ -0,0 +1,37 @@
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+pub struct CatalogEntryIsWorkspacePackageIssue {
+    pub dependency_name: String,
+    /// None = default catalog, Some(name) = named catalog
+    pub catalog_name: Option<String>,
+}
+
+impl Issue for CatalogEntryIsWorkspacePackageIssue {
+    fn name(&self) -> &str {
+        "catalog-entry-is-workspace-package"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let catalog = catalog_label(self.catalog_name.as_deref());
+        format!(
+            "'{}' in {catalog} is also a workspace package. Reference it with \"workspace:*\" instead.",
+            self.dependency_name,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "A catalog entry for a package that lives in the workspace pins a published version, so dependents install it from the registry instead of linking the local source."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+/// Version specs that accept any release, used unless `permissive-specs` is set in the config file.
+pub const DEFAULT_PERMISSIVE_SPECS: &[&str] = &[
+    "*", "", "latest", "x", "X", "x.x", "x.x.x", "*.*.*", "^", "~", ">=0", ">=0.0.0",
+];
+
+pub struct CatalogOverlyPermissiveIssue {
+    pub dependency_name: String,
+    /// None = default catalog, Some(name) = named catalog
+    pub catalog_name: Option<String>,
+    pub version: String,
+}
+
+impl Issue for CatalogOverlyPermissiveIssue {
+    fn name(&self) -> &str {
+        "catalog-overly-permissive"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let catalog = catalog_label(self.catalog_name.as_deref());
+        format!(
+            "'{}' in {catalog} uses \"{}\", which accepts any version",
+            self.dependency_name, self.version,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "A catalog exists to pin one version range for the whole workspace. An unbounded spec like \"*\" or \"latest\" lets every install pick up a new major."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
@@ -0,0 +1,58 @@
+use nodejs_semver::Version;
+
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+pub struct CatalogPrereleaseVersionIssue {
+    pub dependency_name: String,
+    /// None = default catalog, Some(name) = named catalog
+    pub catalog_name: Option<String>,
+    pub version: String,
+    /// The pinned version has pre-release components, e.g. `-beta.3`
+    pub pre_release: bool,
+}
+
+impl Issue for CatalogPrereleaseVersionIssue {
+    fn name(&self) -> &str {
+        "catalog-prerelease-version"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let catalog = catalog_label(self.catalog_name.as_deref());
+        let what = if self.pre_release {
+            "a pre-release version"
+        } else {
+            "a version with build metadata"
+        };
+        format!(
+            "'{}' in {catalog} uses \"{}\", {what}",
+            self.dependency_name, self.version,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "Every package referencing the catalog gets this version. Pre-releases and builds are rarely meant to ship to production; list intended ones under allow-prerelease."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
+
+/// The first version in a range with pre-release or build components, e.g. `2.0.0-beta.3`
+/// in `^2.0.0-beta.3`. Specs that aren't semver, like `workspace:*`, yield None.
+pub fn prerelease_version(range: &str) -> Option<Version> {
+    range
+        .split("||")
+        .flat_map(str::split_whitespace)
+        .map(|token| token.trim_start_matches(['^', '~', '<', '>', '=', 'v']))
+        .filter_map(|token| Version::parse(token).ok())
+        .find(|version| version.is_prerelease() || !version.build().is_empty())
+}
@@ -0,0 +1,40 @@
+use crate::packages::DependencyKind;
+use crate::rules::{Issue, IssueLevel};
+
+/// A dependency listed in several dependency fields of one package with different versions.
+pub struct ConflictingDependencyKindsIssue {
+    pub dependency_name: String,
+    /// Each declaration as written, in field order
+    pub declarations: Vec<(DependencyKind, String)>,
+}
+
+impl Issue for ConflictingDependencyKindsIssue {
+    fn name(&self) -> &str {
+        "conflicting-dependency-kinds"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Error
+    }
+
+    fn message(&self) -> String {
+        let declarations: Vec<String> = self
+            .declarations
+            .iter()
+            .map(|(kind, version)| format!("\"{version}\" in {kind}"))
+            .collect();
+        format!(
+            "'{}' is declared with different versions: {}",
+            self.dependency_name,
+            declarations.join(", "),
+        )
+    }
+
+    fn why(&self) -> &str {
+        "pnpm installs only one of the versions, so the package is built or tested against a version other than the one it declares."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+}
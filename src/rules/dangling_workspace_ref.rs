@@ -0,0 +1,39 @@
+use crate::packages::DependencyKind;
+use crate::rules::{Issue, IssueLevel};
+
+pub struct DanglingWorkspaceRefIssue {
+    pub dependency_name: String,
+    pub version: String,
+    pub kind: DependencyKind,
+    /// The workspace package name the reference resolves to
+    pub target: String,
+}
+
+impl Issue for DanglingWorkspaceRefIssue {
+    fn name(&self) -> &str {
+        "dangling-workspace-ref"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "'{}' references \"{}\" in {} but no workspace package named '{}' exists",
+            self.dependency_name, self.version, self.kind, self.target,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "A workspace: reference must point to a package in the workspace. Dangling references usually come from internal packages that were renamed or removed."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::packages::DependencyKind;
+use crate::rules::{Issue, IssueLevel};
+
+pub struct DependencyNameWhitespaceIssue {
+    /// The name as written in package.json, whitespace included
+    pub dependency_name: String,
+    pub kind: DependencyKind,
+}
+
+impl Issue for DependencyNameWhitespaceIssue {
+    fn name(&self) -> &str {
+        "dependency-name-whitespace"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "\"{}\" in {} has leading or trailing whitespace in its name; did you mean '{}'?",
+            self.dependency_name,
+            self.kind,
+            self.dependency_name.trim(),
+        )
+    }
+
+    fn why(&self) -> &str {
+        "Package names never contain whitespace. A padded key is usually a codegen or editing mistake, and it won't match the catalog entry or package it was meant for."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+}
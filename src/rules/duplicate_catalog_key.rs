@@ -0,0 +1,39 @@
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+pub struct DuplicateCatalogKeyIssue {
+    pub dependency_name: String,
+    /// None = default catalog
+    pub catalog_name: Option<String>,
+    /// The version that takes effect: the last one listed
+    pub version: String,
+}
+
+impl Issue for DuplicateCatalogKeyIssue {
+    fn name(&self) -> &str {
+        "duplicate-catalog-key"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let catalog_desc = catalog_label(self.catalog_name.as_deref());
+        format!(
+            "'{}' is listed more than once in {catalog_desc}; only the last entry (\"{}\") is used",
+            self.dependency_name, self.version,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "A YAML map keeps only the last of two identical keys, so the earlier entry is silently ignored. This is usually left behind by a merge conflict resolution."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
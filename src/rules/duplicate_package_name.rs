@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use crate::rules::{Issue, IssueLevel};
+
+pub struct DuplicatePackageNameIssue {
+    pub package_name: String,
+    /// Every directory whose package.json declares this name, in discovery order
+    pub paths: Vec<PathBuf>,
+}
+
+impl Issue for DuplicatePackageNameIssue {
+    fn name(&self) -> &str {
+        "duplicate-package-name"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Error
+    }
+
+    fn message(&self) -> String {
+        let paths: Vec<String> = self.paths.iter().map(|p| p.display().to_string()).collect();
+        format!(
+            "package name '{}' is declared in {} directories: {}",
+            self.package_name,
+            paths.len(),
+            paths.join(", "),
+        )
+    }
+
+    fn why(&self) -> &str {
+        "pnpm requires every workspace package to have a unique name. With duplicates, workspace: references and filters resolve to only one of them."
+    }
+}
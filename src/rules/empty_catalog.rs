@@ -0,0 +1,30 @@
+use crate::rules::{Issue, IssueLevel};
+
+pub struct EmptyCatalogIssue {
+    pub catalog_name: String,
+}
+
+impl Issue for EmptyCatalogIssue {
+    fn name(&self) -> &str {
+        "empty-catalog"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "catalog \"{}\" is declared but has no entries",
+            self.catalog_name
+        )
+    }
+
+    fn why(&self) -> &str {
+        "An empty named catalog can't resolve any catalog: reference. It is usually a leftover from removing entries, or entries were added under the wrong key."
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(self.catalog_name.clone())
+    }
+}
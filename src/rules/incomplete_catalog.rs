@@ -0,0 +1,42 @@
+use crate::rules::{Issue, IssueLevel};
+
+pub struct IncompleteCatalogIssue {
+    pub catalog_name: String,
+    /// Dependencies referenced through the catalog but missing from it, sorted
+    pub missing: Vec<String>,
+    /// Packages with at least one of those references, always two or more
+    pub packages: usize,
+}
+
+impl Issue for IncompleteCatalogIssue {
+    fn name(&self) -> &str {
+        "incomplete-catalog"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let missing: Vec<String> = self.missing.iter().map(|dep| format!("'{dep}'")).collect();
+        let them = if self.missing.len() == 1 {
+            "it"
+        } else {
+            "them"
+        };
+        format!(
+            "{} packages reference {} as \"catalog:{name}\", but catalog \"{name}\" doesn't define {them}. Add {them} to the catalog.",
+            self.packages,
+            missing.join(", "),
+            name = self.catalog_name,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "Packages that take some of their dependencies from a catalog usually expect it to cover the related ones too. Completing the catalog fixes every catalog-entry-exists error about it at once."
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(self.catalog_name.clone())
+    }
+}
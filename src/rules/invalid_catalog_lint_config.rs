@@ -0,0 +1,24 @@
+use crate::rules::{Issue, IssueLevel};
+
+pub struct InvalidCatalogLintConfigIssue {
+    /// What's wrong with the package's exceptions
+    pub problem: String,
+}
+
+impl Issue for InvalidCatalogLintConfigIssue {
+    fn name(&self) -> &str {
+        "invalid-catalog-lint-config"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Error
+    }
+
+    fn message(&self) -> String {
+        self.problem.clone()
+    }
+
+    fn why(&self) -> &str {
+        "A package's exceptions that can't be read or name no known rule silently do nothing, so the issues they were meant to silence keep being reported, or a typo hides the intent."
+    }
+}
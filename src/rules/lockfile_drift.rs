@@ -0,0 +1,42 @@
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+pub struct LockfileDriftIssue {
+    pub dependency_name: String,
+    /// None = default catalog, Some(name) = named catalog
+    pub catalog_name: Option<String>,
+    pub version: String,
+    /// Versions of the dependency resolved in pnpm-lock.yaml
+    pub resolved: Vec<String>,
+}
+
+impl Issue for LockfileDriftIssue {
+    fn name(&self) -> &str {
+        "lockfile-drift"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let catalog = catalog_label(self.catalog_name.as_deref());
+        format!(
+            "'{}' (\"{}\") in {catalog} matches none of the versions in pnpm-lock.yaml ({})",
+            self.dependency_name,
+            self.version,
+            self.resolved.join(", "),
+        )
+    }
+
+    fn why(&self) -> &str {
+        "The catalog range and the lockfile have drifted apart, usually because the catalog was edited without running pnpm install. The next install will change what gets resolved."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
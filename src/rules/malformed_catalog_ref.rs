@@ -0,0 +1,44 @@
+use crate::packages::{DependencyKind, is_miscased_catalog_ref};
+use crate::rules::{Issue, IssueLevel};
+
+pub struct MalformedCatalogRefIssue {
+    pub dependency_name: String,
+    pub version: String,
+    pub kind: DependencyKind,
+    /// The correctly formed catalog reference
+    pub suggestion: String,
+}
+
+impl Issue for MalformedCatalogRefIssue {
+    fn name(&self) -> &str {
+        "malformed-catalog-ref"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Error
+    }
+
+    fn message(&self) -> String {
+        let problem = if is_miscased_catalog_ref(self.version.trim()) {
+            "the catalog: protocol must be lowercase"
+        } else {
+            "the catalog name can't be surrounded by whitespace or slashes"
+        };
+        format!(
+            "'{}' uses \"{}\" in {} but {problem}. Use \"{}\" instead.",
+            self.dependency_name, self.version, self.kind, self.suggestion,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "pnpm only recognizes the lowercase catalog: protocol followed by the exact catalog name. Any other casing is treated as an invalid version, and stray whitespace or slashes name a catalog that doesn't exist; either way pnpm install fails."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+}
@@ -1,15 +1,95 @@
 pub mod catalog_entry_exists;
+pub mod catalog_entry_is_workspace_package;
+pub mod catalog_overly_permissive;
+pub mod catalog_prerelease_version;
+pub mod conflicting_dependency_kinds;
+pub mod dangling_workspace_ref;
+pub mod dependency_name_whitespace;
+pub mod duplicate_catalog_key;
+pub mod duplicate_package_name;
+pub mod empty_catalog;
+pub mod incomplete_catalog;
+pub mod invalid_catalog_lint_config;
+pub mod lockfile_drift;
+pub mod malformed_catalog_ref;
 pub mod no_direct_version;
 pub mod no_uncataloged_dependency;
+pub mod peer_catalog_consistency;
+pub mod policy;
+pub mod redundant_catalog_duplication;
+pub mod reference_catalog_drift;
+pub mod registry_version_exists;
+pub mod shared_dependency_uses_catalog;
 pub mod unused_catalog_entry;
 
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use colored::Colorize;
+use serde::Deserialize;
 
-use crate::packages::PackageType;
+use crate::packages::{DependencyKind, PackageType};
 
+/// Names of every built-in rule.
+pub const RULE_NAMES: &[&str] = &[
+    "catalog-entry-exists",
+    "catalog-entry-is-workspace-package",
+    "catalog-overly-permissive",
+    "catalog-prerelease-version",
+    "conflicting-dependency-kinds",
+    "dangling-workspace-ref",
+    "dependency-name-whitespace",
+    "duplicate-catalog-key",
+    "duplicate-package-name",
+    "empty-catalog",
+    "incomplete-catalog",
+    "invalid-catalog-lint-config",
+    "lockfile-drift",
+    "malformed-catalog-ref",
+    "no-direct-version",
+    "no-uncataloged-dependency",
+    "peer-catalog-consistency",
+    "redundant-catalog-duplication",
+    "reference-catalog-drift",
+    "registry-version-exists",
+    "shared-dependency-uses-catalog",
+    "unused-catalog-entry",
+];
+
+/// Rules that only look at the catalogs themselves; the set `--catalog-only` runs.
+pub const CATALOG_RULES: &[&str] = &[
+    "catalog-overly-permissive",
+    "catalog-prerelease-version",
+    "duplicate-catalog-key",
+    "empty-catalog",
+    "lockfile-drift",
+    "redundant-catalog-duplication",
+    "reference-catalog-drift",
+    "registry-version-exists",
+];
+
+/// How a message names a catalog: "the default catalog" or `catalog "<name>"`.
+pub fn catalog_label(catalog_name: Option<&str>) -> String {
+    match catalog_name {
+        None => "the default catalog".to_string(),
+        Some(name) => format!("catalog \"{name}\""),
+    }
+}
+
+/// What [`Issue::catalog`] returns for a catalog: its name, or `default` for the default one.
+pub fn catalog_id(catalog_name: Option<&String>) -> String {
+    catalog_name.map_or_else(|| "default".to_string(), Clone::clone)
+}
+
+/// The catalog an [`Issue::catalog`] id stands for; the reverse of [`catalog_id`].
+pub fn catalog_name_of(id: String) -> Option<String> {
+    (id != "default").then_some(id)
+}
+
+#[derive(Clone, Default)]
 pub enum Filter {
+    #[default]
     None,
     Exclude(Vec<String>),
     Only(Vec<String>),
@@ -25,9 +105,12 @@ impl Filter {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Errors sort before warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IssueLevel {
     Error,
+    #[serde(alias = "warn")]
     Warning,
 }
 
@@ -44,13 +127,128 @@ pub trait Issue {
     fn name(&self) -> &str;
     fn level(&self) -> IssueLevel;
     fn message(&self) -> String;
-    #[allow(dead_code)]
     fn why(&self) -> &str;
+
+    /// The dependency the issue is about, if any.
+    fn dependency(&self) -> Option<&str> {
+        None
+    }
+
+    /// The catalog the issue is about, if any (`default` for the default catalog).
+    fn catalog(&self) -> Option<String> {
+        None
+    }
+
+    /// The dependency field the issue was found in, if any.
+    fn kind(&self) -> Option<DependencyKind> {
+        None
+    }
+
+    /// Whether `--fix` resolves the issue.
+    fn fixable(&self) -> bool {
+        false
+    }
+}
+
+/// The order issues of one package are listed in: errors first, then by rule name,
+/// dependency (issues without one first), and message, so output doesn't depend on the
+/// order rules ran in.
+pub fn issue_order(a: &dyn Issue, b: &dyn Issue) -> Ordering {
+    a.level()
+        .cmp(&b.level())
+        .then_with(|| a.name().cmp(b.name()))
+        .then_with(|| a.dependency().cmp(&b.dependency()))
+        .then_with(|| a.message().cmp(&b.message()))
+}
+
+/// An issue as adjusted by the run: its message from a `[messages]` template,
+/// its level raised by `--strict`, its fix found impossible after the fact.
+struct AdjustedIssue {
+    inner: Box<dyn Issue>,
+    message: Option<String>,
+    level: IssueLevel,
+    fixable: bool,
+}
+
+impl Issue for AdjustedIssue {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn level(&self) -> IssueLevel {
+        self.level
+    }
+
+    fn message(&self) -> String {
+        self.message.clone().unwrap_or_else(|| self.inner.message())
+    }
+
+    fn why(&self) -> &str {
+        self.inner.why()
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        self.inner.dependency()
+    }
+
+    fn catalog(&self) -> Option<String> {
+        self.inner.catalog()
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        self.inner.kind()
+    }
+
+    fn fixable(&self) -> bool {
+        self.fixable
+    }
+}
+
+/// Fill a `[messages]` template for an issue found in `package`. `{message}` is the
+/// built-in message; `{rule}`, `{package}`, `{dependency}`, `{catalog}` and `{kind}`
+/// are empty when the issue has no such value. Other `{...}` text is kept as written.
+fn render_message(template: &str, package: &PackageType, issue: &dyn Issue) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = &rest[start + 1..start + len];
+        match placeholder {
+            "message" => out.push_str(&issue.message()),
+            "rule" => out.push_str(issue.name()),
+            "package" => out.push_str(&package.to_string()),
+            "dependency" => out.push_str(issue.dependency().unwrap_or_default()),
+            "catalog" => out.push_str(&issue.catalog().unwrap_or_default()),
+            "kind" => out.push_str(&issue.kind().map(|k| k.to_string()).unwrap_or_default()),
+            _ => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
 }
 
 pub struct IssuesList {
     issues: Vec<(PackageType, Box<dyn Issue>)>,
     rule_filter: Filter,
+    /// Issues dropped by the rule filter
+    suppressed: usize,
+    /// Packages skipped by the package filter
+    skipped_packages: usize,
+    /// Dependencies skipped by the dependency filter
+    skipped_dependencies: usize,
+    /// `[messages]` templates by rule name, applied as issues are added
+    message_templates: HashMap<String, String>,
+    /// Report warnings as errors (`--strict`)
+    strict: bool,
+    /// Rules whose level was set explicitly; `--strict` leaves them alone
+    pinned_levels: HashSet<String>,
+    /// Rules the package being linted ignores through its `catalogLint` field
+    package_ignored_rules: Vec<String>,
 }
 
 impl IssuesList {
@@ -58,17 +256,87 @@ impl IssuesList {
         Self {
             issues: Vec::new(),
             rule_filter,
+            suppressed: 0,
+            skipped_packages: 0,
+            skipped_dependencies: 0,
+            message_templates: HashMap::new(),
+            strict: false,
+            pinned_levels: HashSet::new(),
+            package_ignored_rules: Vec::new(),
         }
     }
 
+    /// Report every warning as an error, except from the rules in `pinned_levels`.
+    pub fn with_strict(mut self, pinned_levels: HashSet<String>) -> Self {
+        self.strict = true;
+        self.pinned_levels = pinned_levels;
+        self
+    }
+
+    pub fn with_message_templates(mut self, templates: HashMap<String, String>) -> Self {
+        self.message_templates = templates;
+        self
+    }
+
     pub fn is_rule_ignored(&self, rule_name: &str) -> bool {
         self.rule_filter.is_ignored(rule_name)
     }
 
+    /// Also drop issues of these rules until the next call; empty to stop.
+    pub fn set_package_ignored_rules(&mut self, rules: &[String]) {
+        self.package_ignored_rules = rules.to_vec();
+    }
+
     pub fn add(&mut self, package_type: PackageType, issue: Box<dyn Issue>) {
-        if !self.rule_filter.is_ignored(issue.name()) {
-            self.issues.push((package_type, issue));
+        if self.rule_filter.is_ignored(issue.name())
+            || self.package_ignored_rules.iter().any(|r| r == issue.name())
+        {
+            self.suppressed += 1;
+            return;
         }
+        let message = self
+            .message_templates
+            .get(issue.name())
+            .map(|template| render_message(template, &package_type, issue.as_ref()));
+        let promote = self.strict
+            && issue.level() == IssueLevel::Warning
+            && !self.pinned_levels.contains(issue.name());
+        let issue: Box<dyn Issue> = if message.is_some() || promote {
+            let level = if promote {
+                IssueLevel::Error
+            } else {
+                issue.level()
+            };
+            Box::new(AdjustedIssue {
+                fixable: issue.fixable(),
+                inner: issue,
+                message,
+                level,
+            })
+        } else {
+            issue
+        };
+        self.issues.push((package_type, issue));
+    }
+
+    pub fn skip_package(&mut self) {
+        self.skipped_packages += 1;
+    }
+
+    pub fn skip_dependency(&mut self) {
+        self.skipped_dependencies += 1;
+    }
+
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed
+    }
+
+    pub fn skipped_packages(&self) -> usize {
+        self.skipped_packages
+    }
+
+    pub fn skipped_dependencies(&self) -> usize {
+        self.skipped_dependencies
     }
 
     pub fn errors_count(&self) -> usize {
@@ -89,6 +357,63 @@ impl IssuesList {
         self.issues.retain(|(_, issue)| issue.name() != rule_name);
     }
 
+    /// Drop the issues of a rule that `--fix` resolved, keeping those it left alone.
+    pub fn remove_fixed(&mut self, rule_name: &str) {
+        self.issues
+            .retain(|(_, issue)| issue.name() != rule_name || !issue.fixable());
+    }
+
+    /// Mark the issues of a rule about any of `dependencies` as not fixable, for fixes
+    /// that turn out to be impossible once every package has been linted.
+    pub fn mark_unfixable(&mut self, rule_name: &str, dependencies: &HashSet<String>) {
+        self.issues = std::mem::take(&mut self.issues)
+            .into_iter()
+            .map(|(package_type, issue)| {
+                let unfixable = issue.name() == rule_name
+                    && issue.fixable()
+                    && issue
+                        .dependency()
+                        .is_some_and(|dep| dependencies.contains(dep));
+                if !unfixable {
+                    return (package_type, issue);
+                }
+                let issue: Box<dyn Issue> = Box::new(AdjustedIssue {
+                    message: None,
+                    level: issue.level(),
+                    fixable: false,
+                    inner: issue,
+                });
+                (package_type, issue)
+            })
+            .collect();
+    }
+
+    /// Reorder issues by a key computed from their package, keeping the order within a package.
+    pub fn sort_by_package<K: Ord>(&mut self, mut key: impl FnMut(&PackageType) -> K) {
+        self.issues
+            .sort_by_key(|(package_type, _)| key(package_type));
+    }
+
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    /// Number of issues per rule or policy, most frequent first, ties by name.
+    pub fn rule_counts(&self) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (_, issue) in &self.issues {
+            *counts.entry(issue.name()).or_default() += 1;
+        }
+        let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        counts
+    }
+
+    /// Issues added after the first `start` ones.
+    pub fn since(&self, start: usize) -> &[(PackageType, Box<dyn Issue>)] {
+        &self.issues[start..]
+    }
+
     pub fn is_empty(&self) -> bool {
         self.issues.is_empty()
     }
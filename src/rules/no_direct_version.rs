@@ -1,5 +1,5 @@
 use crate::packages::DependencyKind;
-use crate::rules::{Issue, IssueLevel};
+use crate::rules::{Issue, IssueLevel, catalog_id};
 
 pub struct NoDirectVersionIssue {
     pub dependency_name: String,
@@ -38,4 +38,22 @@ impl Issue for NoDirectVersionIssue {
     fn why(&self) -> &str {
         "Dependencies available in the catalog should use the catalog: protocol to ensure version consistency across the monorepo."
     }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        let first = self.available_in.first()?;
+        Some(catalog_id(first.as_ref()))
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+
+    /// Only a dependency in a single catalog has an unambiguous `catalog:` reference.
+    fn fixable(&self) -> bool {
+        self.available_in.len() == 1
+    }
 }
@@ -26,4 +26,17 @@ impl Issue for NoUncatalogedDependencyIssue {
     fn why(&self) -> &str {
         "All dependencies should be managed through the pnpm catalog for version consistency across the monorepo."
     }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+
+    /// Unless packages use different versions, which the run marks once every package is in
+    fn fixable(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,47 @@
+use crate::packages::DependencyKind;
+use crate::rules::{Issue, IssueLevel};
+
+/// A `catalog:` peer whose resolved range can't be met by the same package's own
+/// declaration of that dependency in another field.
+pub struct PeerCatalogConsistencyIssue {
+    pub dependency_name: String,
+    /// The peer's catalog: reference, as written
+    pub peer_ref: String,
+    /// The range the peer reference resolves to
+    pub peer_range: String,
+    pub other_kind: DependencyKind,
+    /// The other declaration, as written
+    pub other_version: String,
+    /// The range the other declaration resolves to (differs from `other_version` for catalog: refs)
+    pub other_range: String,
+}
+
+impl Issue for PeerCatalogConsistencyIssue {
+    fn name(&self) -> &str {
+        "peer-catalog-consistency"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let other = if self.other_version == self.other_range {
+            format!("\"{}\"", self.other_version)
+        } else {
+            format!("\"{}\" (\"{}\")", self.other_version, self.other_range)
+        };
+        format!(
+            "'{}' in peerDependencies resolves \"{}\" to \"{}\", which doesn't overlap {} in {}",
+            self.dependency_name, self.peer_ref, self.peer_range, other, self.other_kind,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "A package that declares a peer range it can't satisfy itself is tested against a version its consumers can never install."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::packages::{DependencyKind, parse_catalog_ref};
+use crate::rules::{Issue, IssueLevel, catalog_id};
+
+/// A violation of a user-defined `[[policy]]` from `pnpm-catalog-lint.toml`.
+pub struct PolicyIssue {
+    /// The policy name, reported as the rule name
+    pub policy: String,
+    pub level: IssueLevel,
+    pub dependency_name: String,
+    pub version: String,
+    pub kind: DependencyKind,
+    /// The catalog: reference the policy requires
+    pub expected_ref: String,
+}
+
+impl Issue for PolicyIssue {
+    fn name(&self) -> &str {
+        &self.policy
+    }
+
+    fn level(&self) -> IssueLevel {
+        self.level
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "'{}' uses \"{}\" in {} but policy requires \"{}\"",
+            self.dependency_name, self.version, self.kind, self.expected_ref,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "This dependency is covered by a policy in pnpm-catalog-lint.toml that requires it to come from a specific catalog."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(parse_catalog_ref(&self.expected_ref)?.as_ref()))
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::rules::{Issue, IssueLevel};
+
+pub struct RedundantCatalogDuplicationIssue {
+    pub dependency_name: String,
+    pub version: String,
+    /// Every catalog pinning this exact version, default catalog (None) first
+    pub catalogs: Vec<Option<String>>,
+}
+
+impl Issue for RedundantCatalogDuplicationIssue {
+    fn name(&self) -> &str {
+        "redundant-catalog-duplication"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let named: Vec<String> = self
+            .catalogs
+            .iter()
+            .flatten()
+            .map(|name| format!("\"{name}\""))
+            .collect();
+
+        if self.catalogs.contains(&None) {
+            format!(
+                "'{}' (\"{}\") in catalog {} duplicates the default catalog entry. Reference it with \"catalog:\" instead.",
+                self.dependency_name,
+                self.version,
+                named.join(", "),
+            )
+        } else {
+            format!(
+                "'{}' (\"{}\") is pinned identically in catalogs {}. Consider consolidating it into the default catalog.",
+                self.dependency_name,
+                self.version,
+                named.join(", "),
+            )
+        }
+    }
+
+    fn why(&self) -> &str {
+        "Pinning the same version of a dependency in several catalogs is redundant: each copy has to be bumped separately and they can silently drift apart."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+}
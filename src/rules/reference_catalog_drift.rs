@@ -0,0 +1,40 @@
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+pub struct ReferenceCatalogDriftIssue {
+    pub dependency_name: String,
+    /// None = default catalog, Some(name) = named catalog
+    pub catalog_name: Option<String>,
+    pub version: String,
+    /// The version the reference catalog pins
+    pub reference_version: String,
+}
+
+impl Issue for ReferenceCatalogDriftIssue {
+    fn name(&self) -> &str {
+        "reference-catalog-drift"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Warning
+    }
+
+    fn message(&self) -> String {
+        let catalog = catalog_label(self.catalog_name.as_deref());
+        format!(
+            "'{}' is \"{}\" in {catalog} but \"{}\" in the reference catalog",
+            self.dependency_name, self.version, self.reference_version,
+        )
+    }
+
+    fn why(&self) -> &str {
+        "The organization's reference catalog sets the versions every repository should use. A local version that differs has drifted from it, usually because one side was updated without the other."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
@@ -0,0 +1,53 @@
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
+
+#[derive(Debug)]
+pub enum RegistryMismatch {
+    /// The registry has no package with this name
+    NotPublished,
+    /// The package exists but no published version satisfies the range
+    NoMatchingVersion,
+}
+
+pub struct RegistryVersionExistsIssue {
+    pub dependency_name: String,
+    /// None = default catalog, Some(name) = named catalog
+    pub catalog_name: Option<String>,
+    pub version: String,
+    pub mismatch: RegistryMismatch,
+}
+
+impl Issue for RegistryVersionExistsIssue {
+    fn name(&self) -> &str {
+        "registry-version-exists"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Error
+    }
+
+    fn message(&self) -> String {
+        let catalog = catalog_label(self.catalog_name.as_deref());
+        match self.mismatch {
+            RegistryMismatch::NotPublished => format!(
+                "'{}' in {catalog} is not published to the registry",
+                self.dependency_name,
+            ),
+            RegistryMismatch::NoMatchingVersion => format!(
+                "'{}' (\"{}\") in {catalog} matches no version published to the registry",
+                self.dependency_name, self.version,
+            ),
+        }
+    }
+
+    fn why(&self) -> &str {
+        "A catalog range that matches no published version, usually a typo or a yanked release, makes pnpm install fail for every package using the entry."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+}
@@ -0,0 +1,59 @@
+use crate::packages::DependencyKind;
+use crate::rules::{Issue, IssueLevel};
+
+#[derive(Debug)]
+pub enum SharedViolation {
+    /// The dependency is not in any catalog
+    Uncataloged,
+    /// The dependency is cataloged but this package uses a direct version
+    DirectVersion,
+}
+
+pub struct SharedDependencyUsesCatalogIssue {
+    pub dependency_name: String,
+    pub version: String,
+    pub kind: DependencyKind,
+    /// Number of packages depending on it
+    pub shared_by: usize,
+    pub threshold: usize,
+    pub violation: SharedViolation,
+}
+
+impl Issue for SharedDependencyUsesCatalogIssue {
+    fn name(&self) -> &str {
+        "shared-dependency-uses-catalog"
+    }
+
+    fn level(&self) -> IssueLevel {
+        IssueLevel::Error
+    }
+
+    fn message(&self) -> String {
+        let shared = format!(
+            "'{}' is used by {} packages (threshold {})",
+            self.dependency_name, self.shared_by, self.threshold,
+        );
+        match self.violation {
+            SharedViolation::Uncataloged => format!(
+                "{shared} but \"{}\" in {} is not in any catalog",
+                self.version, self.kind,
+            ),
+            SharedViolation::DirectVersion => format!(
+                "{shared} but {} uses \"{}\" instead of a catalog: reference",
+                self.kind, self.version,
+            ),
+        }
+    }
+
+    fn why(&self) -> &str {
+        "Dependencies shared by many packages are the ones most likely to drift apart. Once a dependency crosses --catalog-threshold it must live in a catalog and be referenced via catalog:."
+    }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn kind(&self) -> Option<DependencyKind> {
+        Some(self.kind)
+    }
+}
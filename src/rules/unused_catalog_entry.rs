@@ -1,4 +1,4 @@
-use crate::rules::{Issue, IssueLevel};
+use crate::rules::{Issue, IssueLevel, catalog_id, catalog_label};
 
 pub struct UnusedCatalogEntryIssue {
     pub dependency_name: String,
@@ -17,23 +17,27 @@ impl Issue for UnusedCatalogEntryIssue {
     }
 
     fn message(&self) -> String {
-        match &self.catalog_name {
-            None => {
-                format!(
-                    "'{}' (\"{}\") in the default catalog is never referenced",
-                    self.dependency_name, self.version,
-                )
-            }
-            Some(name) => {
-                format!(
-                    "'{}' (\"{}\") in catalog \"{name}\" is never referenced",
-                    self.dependency_name, self.version,
-                )
-            }
-        }
+        format!(
+            "'{}' (\"{}\") in {} is never referenced",
+            self.dependency_name,
+            self.version,
+            catalog_label(self.catalog_name.as_deref()),
+        )
     }
 
     fn why(&self) -> &str {
         "Unused catalog entries add noise to pnpm-workspace.yaml and may indicate stale dependencies that should be removed."
     }
+
+    fn dependency(&self) -> Option<&str> {
+        Some(&self.dependency_name)
+    }
+
+    fn catalog(&self) -> Option<String> {
+        Some(catalog_id(self.catalog_name.as_ref()))
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::packages::{Package, PackageType, VersionKind, classify_version};
+use crate::workspace::{CatalogEntry, WorkspaceCatalogs};
+
+/// How many packages reference a single catalog entry. A package listing the dependency
+/// in several fields counts once per kind of reference.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EntryUsage {
+    /// Packages referencing it through the catalog: protocol
+    pub catalog_packages: usize,
+    /// Packages using a direct version of the cataloged dependency
+    pub direct_packages: usize,
+}
+
+/// How many references to cataloged dependencies go through catalog:, for `--min-coverage`.
+/// Every dependency field counts, so a package can contribute more than one reference.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    /// References resolved through the catalog: protocol
+    pub catalog_refs: usize,
+    /// Direct versions of dependencies that have a catalog entry
+    pub direct_refs: usize,
+}
+
+impl Coverage {
+    /// Percentage of references using catalog:. With no cataloged references at all,
+    /// nothing is missing, so coverage is 100.
+    pub fn percent(&self) -> f64 {
+        let total = self.catalog_refs + self.direct_refs;
+        if total == 0 {
+            100.0
+        } else {
+            self.catalog_refs as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Usage bookkeeping for every catalog entry, collected alongside issues.
+#[derive(Debug, Default)]
+pub struct CatalogUsage {
+    catalog_refs: HashMap<CatalogEntry, HashSet<PackageType>>,
+    direct_refs: HashMap<CatalogEntry, HashSet<PackageType>>,
+    coverage: Coverage,
+}
+
+impl CatalogUsage {
+    pub fn record_catalog_ref(&mut self, entry: CatalogEntry, package: &PackageType) {
+        self.catalog_refs
+            .entry(entry)
+            .or_default()
+            .insert(package.clone());
+        self.coverage.catalog_refs += 1;
+    }
+
+    /// A direct version of a dependency cataloged in each of `entries`.
+    pub fn record_direct_ref(&mut self, entries: Vec<CatalogEntry>, package: &PackageType) {
+        for entry in entries {
+            self.direct_refs
+                .entry(entry)
+                .or_default()
+                .insert(package.clone());
+        }
+        self.coverage.direct_refs += 1;
+    }
+
+    pub fn coverage(&self) -> Coverage {
+        self.coverage
+    }
+
+    pub fn get(&self, entry: &CatalogEntry) -> EntryUsage {
+        let count = |refs: &HashMap<CatalogEntry, HashSet<PackageType>>| {
+            refs.get(entry).map_or(0, HashSet::len)
+        };
+        EntryUsage {
+            catalog_packages: count(&self.catalog_refs),
+            direct_packages: count(&self.direct_refs),
+        }
+    }
+
+    /// Every entry of every catalog with its version and usage, in the same order as
+    /// `summarize`, for `catalog-report`.
+    pub fn entry_report(&self, catalogs: &WorkspaceCatalogs) -> Vec<EntryReport> {
+        let default = catalogs.default().iter().map(|dep| (None, dep));
+        let named = catalogs
+            .named()
+            .iter()
+            .flat_map(|(name, deps)| deps.iter().map(move |dep| (Some(name), dep)));
+        default
+            .chain(named)
+            .map(|(catalog_name, (dep_name, version))| {
+                let usage = self.get(&CatalogEntry {
+                    catalog_name: catalog_name.cloned(),
+                    dependency_name: dep_name.clone(),
+                });
+                EntryReport {
+                    catalog: catalog_name
+                        .cloned()
+                        .unwrap_or_else(|| "default".to_string()),
+                    dependency: dep_name.clone(),
+                    version: version.clone(),
+                    catalog_packages: usage.catalog_packages,
+                    direct_packages: usage.direct_packages,
+                }
+            })
+            .collect()
+    }
+
+    /// Summarize usage per catalog, default catalog first, then named catalogs in file order.
+    pub fn summarize(&self, catalogs: &WorkspaceCatalogs) -> Vec<CatalogStats> {
+        let mut stats = Vec::new();
+        stats.push(self.summarize_catalog(None, catalogs.default().keys()));
+        for (name, deps) in catalogs.named() {
+            stats.push(self.summarize_catalog(Some(name), deps.keys()));
+        }
+        stats
+    }
+
+    fn summarize_catalog<'a>(
+        &self,
+        catalog_name: Option<&String>,
+        dep_names: impl Iterator<Item = &'a String>,
+    ) -> CatalogStats {
+        let mut stats = CatalogStats {
+            name: catalog_name
+                .cloned()
+                .unwrap_or_else(|| "default".to_string()),
+            entries: 0,
+            catalog_refs: 0,
+            direct_only: 0,
+            unused: 0,
+        };
+        for dep_name in dep_names {
+            let usage = self.get(&CatalogEntry {
+                catalog_name: catalog_name.cloned(),
+                dependency_name: dep_name.clone(),
+            });
+            stats.entries += 1;
+            if usage.catalog_packages > 0 {
+                stats.catalog_refs += 1;
+            } else if usage.direct_packages > 0 {
+                stats.direct_only += 1;
+            } else {
+                stats.unused += 1;
+            }
+        }
+        stats
+    }
+}
+
+/// Per-catalog usage breakdown printed by `--stats`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct CatalogStats {
+    pub name: String,
+    /// Number of entries defined in the catalog
+    pub entries: usize,
+    /// Entries referenced at least once via the catalog: protocol
+    pub catalog_refs: usize,
+    /// Entries referenced only via direct versions
+    pub direct_only: usize,
+    /// Entries not referenced at all
+    pub unused: usize,
+}
+
+/// One catalog entry and the packages using it, printed by `catalog-report`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct EntryReport {
+    pub catalog: String,
+    pub dependency: String,
+    pub version: String,
+    /// Packages referencing the entry through the catalog: protocol
+    pub catalog_packages: usize,
+    /// Packages using a direct version instead
+    pub direct_packages: usize,
+}
+
+/// How many packages depend on each external dependency, computed once per run.
+/// A package counts once per dependency, however many dependency fields list it.
+/// `VersionKind::Special` references (`workspace:`, `link:`, `jsr:`…) aren't counted.
+#[derive(Debug, Default)]
+pub struct DependencySpread {
+    packages: HashMap<String, usize>,
+}
+
+impl DependencySpread {
+    pub fn collect(packages: &[Package]) -> Self {
+        let mut spread = Self::default();
+        for pkg in packages {
+            let names: HashSet<String> = pkg
+                .all_dependencies()
+                .into_iter()
+                .filter(|dep| !matches!(classify_version(&dep.version), VersionKind::Special(_)))
+                .map(|dep| dep.name)
+                .collect();
+            for name in names {
+                *spread.packages.entry(name).or_default() += 1;
+            }
+        }
+        spread
+    }
+
+    /// Number of packages depending on `dependency_name`.
+    pub fn package_count(&self, dependency_name: &str) -> usize {
+        self.packages
+            .get(dependency_name)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packages::{PackageJson, PackageType};
+    use indexmap::IndexMap;
+    use std::path::PathBuf;
+
+    fn entry(catalog_name: Option<&str>, dep: &str) -> CatalogEntry {
+        CatalogEntry {
+            catalog_name: catalog_name.map(|s| s.to_string()),
+            dependency_name: dep.to_string(),
+        }
+    }
+
+    #[test]
+    fn summarize_counts_each_entry_once() {
+        let mut default = IndexMap::new();
+        default.insert("react".to_string(), "^18.2.0".to_string());
+        default.insert("lodash".to_string(), "^4.17.21".to_string());
+        default.insert("leftpad".to_string(), "^1.0.0".to_string());
+        let mut legacy = IndexMap::new();
+        legacy.insert("jquery".to_string(), "^3.6.0".to_string());
+        let mut named = IndexMap::new();
+        named.insert("legacy".to_string(), legacy);
+        let catalogs = WorkspaceCatalogs::new(default, named);
+
+        let (a, b) = (
+            PackageType::Workspace("a".to_string()),
+            PackageType::Workspace("b".to_string()),
+        );
+        let mut usage = CatalogUsage::default();
+        usage.record_catalog_ref(entry(None, "react"), &a);
+        usage.record_catalog_ref(entry(None, "react"), &b);
+        // e.g. the same catalog: reference in devDependencies
+        usage.record_catalog_ref(entry(None, "react"), &b);
+        usage.record_direct_ref(vec![entry(None, "react")], &a);
+        usage.record_direct_ref(vec![entry(None, "lodash")], &a);
+        usage.record_direct_ref(vec![entry(Some("legacy"), "jquery")], &b);
+
+        let stats = usage.summarize(&catalogs);
+        assert_eq!(
+            stats,
+            vec![
+                CatalogStats {
+                    name: "default".to_string(),
+                    entries: 3,
+                    catalog_refs: 1,
+                    direct_only: 1,
+                    unused: 1,
+                },
+                CatalogStats {
+                    name: "legacy".to_string(),
+                    entries: 1,
+                    catalog_refs: 0,
+                    direct_only: 1,
+                    unused: 0,
+                },
+            ]
+        );
+
+        let report = usage.entry_report(&catalogs);
+        let rows: Vec<(&str, &str, &str, usize, usize)> = report
+            .iter()
+            .map(|r| {
+                (
+                    r.catalog.as_str(),
+                    r.dependency.as_str(),
+                    r.version.as_str(),
+                    r.catalog_packages,
+                    r.direct_packages,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("default", "react", "^18.2.0", 2, 1),
+                ("default", "lodash", "^4.17.21", 0, 1),
+                ("default", "leftpad", "^1.0.0", 0, 0),
+                ("legacy", "jquery", "^3.6.0", 0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn coverage_counts_references_and_is_full_without_any() {
+        assert_eq!(Coverage::default().percent(), 100.0);
+
+        let app = PackageType::Workspace("app".to_string());
+        let mut usage = CatalogUsage::default();
+        usage.record_catalog_ref(entry(None, "react"), &app);
+        usage.record_catalog_ref(entry(None, "lodash"), &app);
+        usage.record_catalog_ref(entry(None, "vite"), &app);
+        // Cataloged in two catalogs, still one reference
+        usage.record_direct_ref(
+            vec![entry(None, "jquery"), entry(Some("legacy"), "jquery")],
+            &app,
+        );
+
+        assert_eq!(
+            usage.coverage(),
+            Coverage {
+                catalog_refs: 3,
+                direct_refs: 1,
+            }
+        );
+        assert_eq!(usage.coverage().percent(), 75.0);
+    }
+
+    #[test]
+    fn spread_counts_each_package_once() {
+        let package = |name: &str, deps: &[(&str, &str)], peers: &[(&str, &str)]| Package {
+            path: PathBuf::from(format!("/fake/{name}")),
+            package_type: PackageType::Workspace(name.to_string()),
+            inner: PackageJson {
+                name: Some(name.to_string()),
+                dependencies: deps
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                peer_dependencies: peers
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                ..Default::default()
+            },
+            pattern: None,
+        };
+        let packages = vec![
+            package(
+                "a",
+                &[("react", "catalog:"), ("ui", "workspace:*")],
+                &[("react", "^18.0.0")],
+            ),
+            package("b", &[("react", "^18.2.0"), ("ui", "workspace:*")], &[]),
+            package("c", &[("lodash", "^4.17.21")], &[]),
+        ];
+
+        let spread = DependencySpread::collect(&packages);
+
+        assert_eq!(spread.package_count("react"), 2);
+        assert_eq!(spread.package_count("lodash"), 1);
+        assert_eq!(spread.package_count("ui"), 0);
+        assert_eq!(spread.package_count("vite"), 0);
+    }
+}
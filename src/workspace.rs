@@ -1,22 +1,185 @@
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
-#[derive(Debug, Deserialize)]
+use crate::edits::FileEdits;
+use crate::error::LintError;
+
+#[derive(Debug, Default, Deserialize)]
 pub struct PnpmWorkspaceYaml {
     #[serde(default)]
     pub packages: Vec<String>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
     pub catalog: IndexMap<String, String>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_catalogs")]
     pub catalogs: IndexMap<String, IndexMap<String, String>>,
 }
 
+/// A catalog entry's value: a version, or a list of acceptable versions. Only the first
+/// of a list is what `catalog:` resolves to; the others are accepted as direct versions.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CatalogVersion {
+    One(String),
+    Any(Vec<String>),
+}
+
+impl CatalogVersion {
+    fn into_versions(self) -> Vec<String> {
+        match self {
+            CatalogVersion::One(version) => vec![version],
+            CatalogVersion::Any(versions) => versions,
+        }
+    }
+}
+
+/// Catalog entries with the first version of each list.
+fn first_versions<E: serde::de::Error>(
+    entries: IndexMap<String, CatalogVersion>,
+) -> std::result::Result<IndexMap<String, String>, E> {
+    entries
+        .into_iter()
+        .map(
+            |(name, version)| match version.into_versions().into_iter().next() {
+                Some(first) => Ok((name, first)),
+                None => Err(E::custom(format!(
+                    "catalog entry '{name}' has an empty list of versions"
+                ))),
+            },
+        )
+        .collect()
+}
+
+/// A map that may be written as null, e.g. `catalog:` with nothing after it, read as empty.
+fn deserialize_null_as_empty<'de, D>(
+    deserializer: D,
+) -> std::result::Result<IndexMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    first_versions(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Named catalogs, reading a catalog declared without entries (`react16:`) as empty,
+/// and a null `catalogs:` as no named catalogs.
+fn deserialize_catalogs<'de, D>(
+    deserializer: D,
+) -> std::result::Result<IndexMap<String, IndexMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let catalogs: Option<IndexMap<String, Option<IndexMap<String, CatalogVersion>>>> =
+        Option::deserialize(deserializer)?;
+    catalogs
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, entries)| Ok((name, first_versions(entries.unwrap_or_default())?)))
+        .collect()
+}
+
+/// Catalog entries in file order, duplicate keys included. A plain map keeps only the last
+/// of two identical keys, and only the first version of a list, so this is read in a second
+/// pass for what the maps lose.
+#[derive(Debug, Default, Deserialize)]
+struct RawCatalogsYaml {
+    #[serde(default)]
+    catalog: Option<RawCatalog>,
+    #[serde(default)]
+    catalogs: Option<IndexMap<String, Option<RawCatalog>>>,
+}
+
+#[derive(Debug, Default)]
+struct RawCatalog(Vec<(String, CatalogVersion)>);
+
+impl<'de> Deserialize<'de> for RawCatalog {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct EntriesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for EntriesVisitor {
+            type Value = RawCatalog;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map of dependency names to versions")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    entries.push((key, map.next_value()?));
+                }
+                Ok(RawCatalog(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// Every catalog with its raw entries, the default catalog first.
+fn raw_catalogs(content: &str) -> Vec<(Option<String>, RawCatalog)> {
+    // The content already parsed as a workspace, so this can't fail in practice
+    let yaml: RawCatalogsYaml = serde_yaml::from_str(content).unwrap_or_default();
+    let named = yaml
+        .catalogs
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, entries)| {
+            // `catalogs.default` is the default catalog
+            let catalog_name = (name != "default").then_some(name);
+            (catalog_name, entries.unwrap_or_default())
+        });
+    std::iter::once((None, yaml.catalog.unwrap_or_default()))
+        .chain(named)
+        .collect()
+}
+
+/// Dependencies listed more than once in the same catalog, once each, in file order.
+fn duplicate_catalog_keys(raw: &[(Option<String>, RawCatalog)]) -> Vec<CatalogEntry> {
+    let mut duplicates = Vec::new();
+    for (catalog_name, entries) in raw {
+        let mut seen = HashSet::new();
+        for (key, _) in &entries.0 {
+            let entry = CatalogEntry {
+                catalog_name: catalog_name.clone(),
+                dependency_name: key.clone(),
+            };
+            if !seen.insert(key) && !duplicates.contains(&entry) {
+                duplicates.push(entry);
+            }
+        }
+    }
+    duplicates
+}
+
+/// Entries written as a list of versions. A duplicate key keeps its last value, like the maps.
+fn version_lists(raw: Vec<(Option<String>, RawCatalog)>) -> HashMap<CatalogEntry, Vec<String>> {
+    let mut lists = HashMap::new();
+    for (catalog_name, entries) in raw {
+        for (key, version) in entries.0 {
+            let entry = CatalogEntry {
+                catalog_name: catalog_name.clone(),
+                dependency_name: key,
+            };
+            match version {
+                CatalogVersion::Any(versions) => lists.insert(entry, versions),
+                CatalogVersion::One(_) => lists.remove(&entry),
+            };
+        }
+    }
+    lists
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CatalogEntry {
     /// None = default catalog, Some(name) = named catalog
@@ -24,13 +187,127 @@ pub struct CatalogEntry {
     pub dependency_name: String,
 }
 
+/// Catalogs are read-only once built: every constructor goes through `with_details`, which
+/// indexes them.
 #[derive(Debug)]
 pub struct WorkspaceCatalogs {
-    pub default: IndexMap<String, String>,
-    pub named: IndexMap<String, IndexMap<String, String>>,
+    default: IndexMap<String, String>,
+    named: IndexMap<String, IndexMap<String, String>>,
+    details: CatalogDetails,
+    /// Catalogs defining each dependency, in `find_dependency` order
+    index: HashMap<String, Vec<Option<String>>>,
+}
+
+/// What the workspace file tells about the catalogs beyond their entries; empty for
+/// catalogs that weren't read from a file
+#[derive(Debug, Default)]
+struct CatalogDetails {
+    /// Dependencies listed twice in the same catalog; the last version listed is the
+    /// one in the catalog
+    duplicate_keys: Vec<CatalogEntry>,
+    /// Entries written as a list of acceptable versions, with the whole list; the
+    /// catalog holds the first one
+    version_lists: HashMap<CatalogEntry, Vec<String>>,
+    /// 1-based line of each entry, the last one for a duplicate key
+    entry_lines: HashMap<CatalogEntry, usize>,
+}
+
+impl CatalogDetails {
+    /// Only what's about named catalogs
+    fn without_default(self) -> Self {
+        Self {
+            duplicate_keys: self
+                .duplicate_keys
+                .into_iter()
+                .filter(|entry| entry.catalog_name.is_some())
+                .collect(),
+            version_lists: self
+                .version_lists
+                .into_iter()
+                .filter(|(entry, _)| entry.catalog_name.is_some())
+                .collect(),
+            entry_lines: self
+                .entry_lines
+                .into_iter()
+                .filter(|(entry, _)| entry.catalog_name.is_some())
+                .collect(),
+        }
+    }
 }
 
 impl WorkspaceCatalogs {
+    /// Catalogs with nothing but their entries, as tests build them
+    #[cfg(test)]
+    pub fn new(
+        default: IndexMap<String, String>,
+        named: IndexMap<String, IndexMap<String, String>>,
+    ) -> Self {
+        Self::with_details(default, named, CatalogDetails::default())
+    }
+
+    fn with_details(
+        default: IndexMap<String, String>,
+        named: IndexMap<String, IndexMap<String, String>>,
+        details: CatalogDetails,
+    ) -> Self {
+        let mut index: HashMap<String, Vec<Option<String>>> = HashMap::new();
+        for dep_name in default.keys() {
+            index.entry(dep_name.clone()).or_default().push(None);
+        }
+        for (catalog_name, deps) in &named {
+            for dep_name in deps.keys() {
+                index
+                    .entry(dep_name.clone())
+                    .or_default()
+                    .push(Some(catalog_name.clone()));
+            }
+        }
+        Self {
+            default,
+            named,
+            details,
+            index,
+        }
+    }
+
+    /// Replace the default catalog, e.g. with `--catalog-source`. What was read from the
+    /// workspace file about the old default catalog is dropped.
+    pub fn with_default(self, default: IndexMap<String, String>) -> Self {
+        Self::with_details(default, self.named, self.details.without_default())
+    }
+
+    pub fn default(&self) -> &IndexMap<String, String> {
+        &self.default
+    }
+
+    pub fn named(&self) -> &IndexMap<String, IndexMap<String, String>> {
+        &self.named
+    }
+
+    /// Dependencies listed twice in the same catalog of the workspace file; the last
+    /// version listed is the one in the catalog
+    pub fn duplicate_keys(&self) -> &[CatalogEntry] {
+        &self.details.duplicate_keys
+    }
+
+    /// 1-based line of each entry in the workspace file, the last one for a duplicate key.
+    /// Empty for catalogs that weren't read from a file.
+    pub fn entry_lines(&self) -> &HashMap<CatalogEntry, usize> {
+        &self.details.entry_lines
+    }
+
+    /// Add a named catalog after construction, keeping the index in sync.
+    #[cfg(test)]
+    pub fn insert_named(&mut self, catalog_name: String, entries: IndexMap<String, String>) {
+        for dep_name in entries.keys() {
+            self.index
+                .entry(dep_name.clone())
+                .or_default()
+                .push(Some(catalog_name.clone()));
+        }
+        self.named.insert(catalog_name, entries);
+    }
+
     pub fn has_default_entry(&self, dep_name: &str) -> bool {
         self.default.contains_key(dep_name)
     }
@@ -64,6 +341,18 @@ impl WorkspaceCatalogs {
         entries
     }
 
+    /// Every entry as `(catalog name, dependency, version)`, default catalog first,
+    /// then named catalogs in file order.
+    pub fn entries(&self) -> impl Iterator<Item = (Option<&String>, &String, &String)> {
+        self.default
+            .iter()
+            .map(|(dep, version)| (None, dep, version))
+            .chain(self.named.iter().flat_map(|(catalog, deps)| {
+                deps.iter()
+                    .map(move |(dep, version)| (Some(catalog), dep, version))
+            }))
+    }
+
     pub fn get_version(&self, entry: &CatalogEntry) -> Option<&str> {
         match &entry.catalog_name {
             None => self.default.get(&entry.dependency_name).map(|s| s.as_str()),
@@ -75,36 +364,117 @@ impl WorkspaceCatalogs {
         }
     }
 
+    /// Every version an entry accepts: the whole list for an entry written as one,
+    /// otherwise its single version.
+    pub fn accepted_versions(&self, entry: &CatalogEntry) -> Vec<&str> {
+        match self.details.version_lists.get(entry) {
+            Some(versions) => versions.iter().map(String::as_str).collect(),
+            None => self.get_version(entry).into_iter().collect(),
+        }
+    }
+
+    /// An entry's version as shown in messages; a list is joined with `, `.
+    pub fn display_version(&self, entry: &CatalogEntry) -> Option<String> {
+        self.get_version(entry)?;
+        Some(self.accepted_versions(entry).join(", "))
+    }
+
     /// Check if a dependency name exists in any catalog (default or named).
     /// Returns a list of catalog names where it's found (None = default).
     pub fn find_dependency(&self, dep_name: &str) -> Vec<Option<String>> {
-        let mut found = Vec::new();
-        if self.default.contains_key(dep_name) {
-            found.push(None);
-        }
-        for (catalog_name, deps) in &self.named {
-            if deps.contains_key(dep_name) {
-                found.push(Some(catalog_name.clone()));
-            }
-        }
-        found
+        self.index.get(dep_name).cloned().unwrap_or_default()
     }
 }
 
-pub fn parse_workspace(root: &Path) -> Result<(PnpmWorkspaceYaml, WorkspaceCatalogs)> {
-    let yaml_path = root.join("pnpm-workspace.yaml");
-    let content = std::fs::read_to_string(&yaml_path)
-        .with_context(|| format!("Failed to read {}", yaml_path.display()))?;
+pub fn parse_workspace(
+    root: &Path,
+) -> std::result::Result<(PnpmWorkspaceYaml, WorkspaceCatalogs), LintError> {
+    parse_workspace_file(&root.join("pnpm-workspace.yaml")).map_err(|e| match e {
+        LintError::Io { source, .. } if source.kind() == std::io::ErrorKind::NotFound => {
+            LintError::WorkspaceNotFound(root.to_path_buf())
+        }
+        e => e,
+    })
+}
 
-    let workspace: PnpmWorkspaceYaml =
-        serde_yaml::from_str(&content).context("Failed to parse pnpm-workspace.yaml")?;
+/// Parse a workspace yaml file at any path, e.g. for `--catalog-only`.
+pub fn parse_workspace_file(
+    yaml_path: &Path,
+) -> std::result::Result<(PnpmWorkspaceYaml, WorkspaceCatalogs), LintError> {
+    let content = std::fs::read_to_string(yaml_path).map_err(|source| LintError::Io {
+        path: yaml_path.to_path_buf(),
+        source,
+    })?;
+    parse_workspace_str(&content, yaml_path)
+}
 
-    let catalogs = WorkspaceCatalogs {
-        default: workspace.catalog.clone(),
-        named: workspace.catalogs.clone(),
+/// Parse workspace yaml read from `yaml_path`, which is only used in errors.
+pub fn parse_workspace_str(
+    content: &str,
+    yaml_path: &Path,
+) -> std::result::Result<(PnpmWorkspaceYaml, WorkspaceCatalogs), LintError> {
+    let workspace: PnpmWorkspaceYaml =
+        serde_yaml::from_str(content).map_err(|source| LintError::WorkspaceParse {
+            path: yaml_path.to_path_buf(),
+            source,
+        })?;
+
+    // Like pnpm, `catalogs.default` is another way to write `catalog`, and using both is an error
+    let mut default = workspace.catalog.clone();
+    let mut named = workspace.catalogs.clone();
+    if let Some(entries) = named.shift_remove("default") {
+        if !default.is_empty() && !entries.is_empty() {
+            return Err(LintError::DuplicateDefaultCatalog(yaml_path.to_path_buf()));
+        }
+        default.extend(entries);
+    }
+    let raw = raw_catalogs(content);
+    let details = CatalogDetails {
+        duplicate_keys: duplicate_catalog_keys(&raw),
+        version_lists: version_lists(raw),
+        entry_lines: catalog_entry_lines(content)
+            .into_iter()
+            .map(|(i, entry)| (entry, i + 1))
+            .collect(),
     };
 
-    Ok((workspace, catalogs))
+    Ok((
+        workspace,
+        WorkspaceCatalogs::with_details(default, named, details),
+    ))
+}
+
+/// `path` relative to `base`, climbing out with `..` when it isn't inside it, e.g.
+/// `/repo/packages/app` relative to `/repo/tools` is `../packages/app`.
+/// Both paths should be absolute (or both relative to the same directory).
+pub fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let path: Vec<_> = path.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &path[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+/// Read a `{ name: version }` map to use as the default catalog, e.g. a shared-versions file
+/// kept during a migration from Yarn. `.json` files are parsed as JSON, anything else as YAML.
+pub fn load_catalog_source(path: &Path) -> Result<IndexMap<String, String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let is_json = path.extension().is_some_and(|ext| ext == "json");
+    let parsed = if is_json {
+        serde_json::from_str(&content).map_err(anyhow::Error::from)
+    } else {
+        serde_yaml::from_str::<Option<IndexMap<String, String>>>(&content)
+            .map(Option::unwrap_or_default)
+            .map_err(anyhow::Error::from)
+    };
+    parsed.with_context(|| format!("Failed to parse {}", path.display()))
 }
 
 /// Extract the YAML key from a line like `  react: "^18.2.0"` or `  "@types/react": "^18.0.0"`.
@@ -158,89 +528,115 @@ enum YamlSection {
     NamedCatalog(String),
 }
 
-/// Remove unused catalog entries from `pnpm-workspace.yaml` using line-based editing.
-/// Returns the number of entries removed.
-pub fn remove_catalog_entries(root: &Path, entries: &[CatalogEntry]) -> Result<usize> {
-    let yaml_path = root.join("pnpm-workspace.yaml");
-    let content = std::fs::read_to_string(&yaml_path)
-        .with_context(|| format!("Failed to read {}", yaml_path.display()))?;
-
-    let line_ending = if content.contains("\r\n") {
-        "\r\n"
-    } else {
-        "\n"
-    };
-    let lines: Vec<&str> = content.split('\n').collect();
-
-    // Build a set for quick lookup
-    let to_remove: HashSet<&CatalogEntry> = entries.iter().collect();
-
-    // First pass: identify section context and mark lines for removal
-    let mut remove_lines: HashSet<usize> = HashSet::new();
+/// The catalog entries of workspace yaml with their 0-based line index, in file order,
+/// found line by line like the edits below. `catalogs.default` entries are in the
+/// default catalog.
+fn catalog_entry_lines(content: &str) -> Vec<(usize, CatalogEntry)> {
+    let mut entries = Vec::new();
     let mut section = YamlSection::Other;
-    let mut removed_count = 0;
 
-    for (i, line) in lines.iter().enumerate() {
+    for (i, line) in content.split('\n').enumerate() {
         let trimmed = line.trim_end_matches('\r');
 
         // Detect top-level section transitions (zero indent, non-blank)
         if !trimmed.is_empty() && !trimmed.starts_with(' ') && !trimmed.starts_with('#') {
-            if trimmed == "catalog:"
+            section = if trimmed == "catalog:"
                 || trimmed.starts_with("catalog:") && trimmed[8..].trim().is_empty()
             {
-                section = YamlSection::DefaultCatalog;
-                continue;
+                YamlSection::DefaultCatalog
             } else if trimmed == "catalogs:"
                 || trimmed.starts_with("catalogs:") && trimmed[9..].trim().is_empty()
             {
-                section = YamlSection::CatalogsHeader;
-                continue;
+                YamlSection::CatalogsHeader
             } else {
-                section = YamlSection::Other;
-                continue;
-            }
+                YamlSection::Other
+            };
+            continue;
         }
 
         match &section {
             YamlSection::DefaultCatalog => {
                 if let Some(key) = extract_yaml_key(trimmed, 2) {
-                    let entry = CatalogEntry {
-                        catalog_name: None,
-                        dependency_name: key.to_string(),
-                    };
-                    if to_remove.contains(&entry) {
-                        remove_lines.insert(i);
-                        removed_count += 1;
-                    }
+                    entries.push((
+                        i,
+                        CatalogEntry {
+                            catalog_name: None,
+                            dependency_name: key.to_string(),
+                        },
+                    ));
                 }
             }
             YamlSection::CatalogsHeader => {
-                // Check for named catalog header at indent 2, e.g. `  react16:`
+                // Named catalog header at indent 2, e.g. `  react16:`
                 if let Some(key) = extract_yaml_key(trimmed, 2) {
-                    // This is a named catalog header — but extract_yaml_key looks for `key: value`
-                    // Named catalog headers are `name:` followed by children. Check if it's a section header.
                     section = YamlSection::NamedCatalog(key.to_string());
                 }
             }
             YamlSection::NamedCatalog(catalog_name) => {
-                // Entries at indent 4
+                // Entries at indent 4; `catalogs.default` holds the default catalog
                 if let Some(key) = extract_yaml_key(trimmed, 4) {
-                    let entry = CatalogEntry {
-                        catalog_name: Some(catalog_name.clone()),
-                        dependency_name: key.to_string(),
-                    };
-                    if to_remove.contains(&entry) {
-                        remove_lines.insert(i);
-                        removed_count += 1;
-                    }
-                } else if let Some(_key) = extract_yaml_key(trimmed, 2) {
+                    entries.push((
+                        i,
+                        CatalogEntry {
+                            catalog_name: (catalog_name != "default").then(|| catalog_name.clone()),
+                            dependency_name: key.to_string(),
+                        },
+                    ));
+                } else if let Some(key) = extract_yaml_key(trimmed, 2) {
                     // New named catalog section at indent 2
-                    section = YamlSection::NamedCatalog(_key.to_string());
+                    section = YamlSection::NamedCatalog(key.to_string());
                 }
             }
             YamlSection::Other => {}
         }
     }
+    entries
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Remove unused catalog entries from `pnpm-workspace.yaml` using line-based editing.
+/// Returns the number of entries removed.
+pub fn remove_catalog_entries(
+    edits: &mut FileEdits,
+    root: &Path,
+    entries: &[CatalogEntry],
+) -> Result<usize> {
+    let yaml_path = root.join("pnpm-workspace.yaml");
+    let content = edits.read(&yaml_path)?;
+
+    let line_ending = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<&str> = content.split('\n').collect();
+
+    // Build a set for quick lookup
+    let to_remove: HashSet<&CatalogEntry> = entries.iter().collect();
+
+    // First pass: mark the lines of the entries to remove
+    let mut remove_lines: HashSet<usize> = HashSet::new();
+    let mut removed_count = 0;
+    for (i, entry) in catalog_entry_lines(&content) {
+        if to_remove.contains(&entry) {
+            remove_lines.insert(i);
+            removed_count += 1;
+            // A list of versions written as a block continues on the following lines
+            let indent = indent_of(lines[i]);
+            for (j, line) in lines.iter().enumerate().skip(i + 1) {
+                let line = line.trim_end_matches('\r');
+                let continues = indent_of(line) > indent
+                    || indent_of(line) == indent && line[indent..].starts_with("- ");
+                if line.trim().is_empty() || !continues {
+                    break;
+                }
+                remove_lines.insert(j);
+            }
+        }
+    }
 
     if removed_count == 0 {
         return Ok(0);
@@ -254,7 +650,7 @@ pub fn remove_catalog_entries(root: &Path, entries: &[CatalogEntry]) -> Result<u
     let mut named_catalog_headers: Vec<(usize, String)> = Vec::new();
     let mut named_catalog_has_remaining: HashSet<String> = HashSet::new();
 
-    section = YamlSection::Other;
+    let mut section = YamlSection::Other;
     for (i, line) in lines.iter().enumerate() {
         let trimmed = line.trim_end_matches('\r');
 
@@ -336,15 +732,16 @@ pub fn remove_catalog_entries(root: &Path, entries: &[CatalogEntry]) -> Result<u
         output = output.replace('\n', "\r\n");
     }
 
-    std::fs::write(&yaml_path, &output)
-        .with_context(|| format!("Failed to write {}", yaml_path.display()))?;
+    edits.write(&yaml_path, output);
 
     Ok(removed_count)
 }
 
-/// Add new entries to the default catalog in `pnpm-workspace.yaml` using line-based editing.
+/// Add new entries to the default catalog in `pnpm-workspace.yaml` using line-based editing:
+/// under `catalog:`, or under `catalogs.default` when only that one exists.
 /// Returns the number of entries added.
 pub fn add_catalog_entries(
+    edits: &mut FileEdits,
     root: &Path,
     additions: &[crate::collect::CatalogAddition],
 ) -> Result<usize> {
@@ -353,8 +750,7 @@ pub fn add_catalog_entries(
     }
 
     let yaml_path = root.join("pnpm-workspace.yaml");
-    let content = std::fs::read_to_string(&yaml_path)
-        .with_context(|| format!("Failed to read {}", yaml_path.display()))?;
+    let content = edits.read(&yaml_path)?;
 
     let line_ending = if content.contains("\r\n") {
         "\r\n"
@@ -363,8 +759,9 @@ pub fn add_catalog_entries(
     };
     let lines: Vec<&str> = content.split('\n').collect();
 
-    // Find the catalog: section boundaries and detect indentation + sort order
-    let mut catalog_start: Option<usize> = None; // line index of "catalog:" header
+    // Find the default catalog's boundaries, under `catalog:` or else `catalogs.default`,
+    // and detect indentation + sort order
+    let mut catalog_start: Option<usize> = None; // line index of the section header
     let mut catalog_end: usize = 0; // last entry line index + 1
     let mut existing_keys: Vec<String> = Vec::new();
     let mut indent = String::from("  "); // default 2 spaces
@@ -374,49 +771,74 @@ pub fn add_catalog_entries(
         let trimmed = line.trim_end_matches('\r');
 
         if !trimmed.is_empty() && !trimmed.starts_with(' ') && !trimmed.starts_with('#') {
-            if trimmed == "catalog:"
+            if catalog_start.is_some() {
+                break; // left the default catalog
+            }
+            section = if trimmed == "catalog:"
                 || trimmed.starts_with("catalog:") && trimmed[8..].trim().is_empty()
             {
-                section = YamlSection::DefaultCatalog;
                 catalog_start = Some(i);
                 catalog_end = i + 1;
-                continue;
+                YamlSection::DefaultCatalog
+            } else if trimmed == "catalogs:"
+                || trimmed.starts_with("catalogs:") && trimmed[9..].trim().is_empty()
+            {
+                YamlSection::CatalogsHeader
             } else {
-                if section == YamlSection::DefaultCatalog {
-                    break; // left the catalog section
-                }
-                section = YamlSection::Other;
-                continue;
-            }
+                YamlSection::Other
+            };
+            continue;
         }
 
-        if section == YamlSection::DefaultCatalog {
-            if let Some(key) = extract_yaml_key(trimmed, 2) {
-                if existing_keys.is_empty() {
-                    // Detect indent from first entry
-                    let spaces = line.len() - line.trim_start().len();
-                    indent = " ".repeat(spaces);
-                }
-                existing_keys.push(key.to_string());
-                catalog_end = i + 1;
-            } else {
-                // Also detect tab-based indent
-                let stripped = line.trim_end_matches('\r');
-                if stripped.starts_with('\t')
-                    && let Some(key) = stripped.trim_start().split(':').next()
-                {
-                    let key = key.trim_matches('"').trim_matches('\'');
-                    if !key.is_empty() && !key.starts_with('#') {
-                        if existing_keys.is_empty() {
-                            let tab_count =
-                                stripped.len() - stripped.trim_start_matches('\t').len();
-                            indent = "\t".repeat(tab_count);
+        match &section {
+            YamlSection::DefaultCatalog => {
+                if let Some(key) = extract_yaml_key(trimmed, 2) {
+                    if existing_keys.is_empty() {
+                        // Detect indent from first entry
+                        let spaces = line.len() - line.trim_start().len();
+                        indent = " ".repeat(spaces);
+                    }
+                    existing_keys.push(key.to_string());
+                    catalog_end = i + 1;
+                } else {
+                    // Also detect tab-based indent
+                    let stripped = line.trim_end_matches('\r');
+                    if stripped.starts_with('\t')
+                        && let Some(key) = stripped.trim_start().split(':').next()
+                    {
+                        let key = key.trim_matches('"').trim_matches('\'');
+                        if !key.is_empty() && !key.starts_with('#') {
+                            if existing_keys.is_empty() {
+                                let tab_count =
+                                    stripped.len() - stripped.trim_start_matches('\t').len();
+                                indent = "\t".repeat(tab_count);
+                            }
+                            existing_keys.push(key.to_string());
+                            catalog_end = i + 1;
                         }
-                        existing_keys.push(key.to_string());
+                    }
+                }
+            }
+            YamlSection::NamedCatalog(catalog_name) if catalog_name == "default" => {
+                // `catalogs.default` entries sit at indent 4, under the `  default:` header
+                if let Some(key) = extract_yaml_key(trimmed, 4) {
+                    existing_keys.push(key.to_string());
+                    catalog_end = i + 1;
+                } else if extract_yaml_key(trimmed, 2).is_some() {
+                    break; // next named catalog
+                }
+            }
+            YamlSection::CatalogsHeader | YamlSection::NamedCatalog(_) => {
+                if let Some(key) = extract_yaml_key(trimmed, 2) {
+                    if key == "default" {
+                        catalog_start = Some(i);
                         catalog_end = i + 1;
+                        indent = "    ".to_string();
                     }
+                    section = YamlSection::NamedCatalog(key.to_string());
                 }
             }
+            YamlSection::Other => {}
         }
     }
 
@@ -435,7 +857,7 @@ pub fn add_catalog_entries(
     let mut result_lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
     let mut added_count = 0;
 
-    if let Some(_header_idx) = catalog_start {
+    if let Some(header_idx) = catalog_start {
         if is_sorted {
             // Insert each addition in alphabetical order
             // Process in reverse to maintain correct indices
@@ -450,9 +872,10 @@ pub fn add_catalog_entries(
                     .iter()
                     .position(|k| k.as_str() > addition.dependency_name.as_str())
                     .map(|pos| {
-                        // Find the actual line index for this key position
+                        // Find the actual line index for this key position, counting only
+                        // keys below the section header so other sections aren't miscounted
                         let mut key_count = 0;
-                        for (i, line) in result_lines.iter().enumerate() {
+                        for (i, line) in result_lines.iter().enumerate().skip(header_idx + 1) {
                             let trimmed = line.trim_end_matches('\r');
                             if let Some(key) = extract_yaml_key(trimmed, indent.len()) {
                                 if key_count == pos {
@@ -476,16 +899,14 @@ pub fn add_catalog_entries(
             }
         } else {
             // Not sorted — append to end of catalog section
-            let mut insert_at = catalog_end;
-            for addition in additions {
+            for (insert_at, addition) in (catalog_end..).zip(additions) {
                 let new_line = format_entry(&addition.dependency_name, &addition.version);
                 result_lines.insert(insert_at, new_line);
-                insert_at += 1;
                 added_count += 1;
             }
         }
     } else {
-        // No catalog: section exists — append one at the end
+        // No default catalog section exists — append a catalog: one at the end
         // Add a blank line separator if the file doesn't end with one
         if let Some(last) = result_lines.last()
             && !last.trim_end_matches('\r').is_empty()
@@ -510,8 +931,7 @@ pub fn add_catalog_entries(
         output = output.replace('\n', "\r\n");
     }
 
-    std::fs::write(&yaml_path, &output)
-        .with_context(|| format!("Failed to write {}", yaml_path.display()))?;
+    edits.write(&yaml_path, output);
 
     Ok(added_count)
 }
@@ -520,6 +940,278 @@ pub fn add_catalog_entries(
 mod tests {
     use super::*;
 
+    fn remove_entries(root: &Path, entries: &[CatalogEntry]) -> usize {
+        let mut edits = FileEdits::default();
+        let count = remove_catalog_entries(&mut edits, root, entries).unwrap();
+        edits.write_all().unwrap();
+        count
+    }
+
+    fn add_entries(root: &Path, additions: &[crate::collect::CatalogAddition]) -> usize {
+        let mut edits = FileEdits::default();
+        let count = add_catalog_entries(&mut edits, root, additions).unwrap();
+        edits.write_all().unwrap();
+        count
+    }
+
+    #[test]
+    fn parse_workspace_distinguishes_missing_and_invalid_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            parse_workspace(dir.path()),
+            Err(LintError::WorkspaceNotFound(_))
+        ));
+
+        std::fs::write(dir.path().join("pnpm-workspace.yaml"), "packages: [\n").unwrap();
+        assert!(matches!(
+            parse_workspace(dir.path()),
+            Err(LintError::WorkspaceParse { .. })
+        ));
+    }
+
+    #[test]
+    fn null_catalog_and_catalogs_read_as_empty() {
+        for content in [
+            "packages:\n  - \"packages/*\"\ncatalog:\ncatalogs:\n",
+            "packages:\n  - \"packages/*\"\ncatalog: null\ncatalogs: null\n",
+            "catalog: ~\ncatalogs:\n  react16:\n",
+        ] {
+            let workspace: PnpmWorkspaceYaml = serde_yaml::from_str(content).unwrap();
+            assert!(workspace.catalog.is_empty(), "{content}");
+        }
+
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("catalog:\ncatalogs: null\n").unwrap();
+        assert!(workspace.catalogs.is_empty());
+        let workspace: PnpmWorkspaceYaml =
+            serde_yaml::from_str("catalog: ~\ncatalogs:\n  react16:\n").unwrap();
+        assert!(workspace.catalogs["react16"].is_empty());
+    }
+
+    #[test]
+    fn catalogs_default_is_the_default_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(
+            &path,
+            "catalogs:\n  default:\n    react: \"^18.2.0\"\n  react17:\n    react: \"^17.0.2\"\n",
+        )
+        .unwrap();
+
+        let (_, catalogs) = parse_workspace_file(&path).unwrap();
+        assert!(catalogs.has_default_entry("react"));
+        assert!(!catalogs.has_catalog("default"));
+        assert_eq!(
+            catalogs.find_dependency("react"),
+            vec![None, Some("react17".to_string())]
+        );
+
+        // pnpm rejects a default catalog defined both ways
+        std::fs::write(
+            &path,
+            "catalog:\n  lodash: \"^4.17.21\"\ncatalogs:\n  default:\n    react: \"^18.2.0\"\n",
+        )
+        .unwrap();
+        let err = parse_workspace_file(&path).unwrap_err();
+        assert!(matches!(err, LintError::DuplicateDefaultCatalog(_)));
+        assert!(
+            err.to_string()
+                .starts_with("The default catalog is defined twice")
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_in_a_catalog_are_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(
+            &path,
+            "catalog:\n  react: ^18.2.0\n  lodash: ^4.17.21\n  react: ^18.3.1\n  react: ^18.3.0\n\
+             catalogs:\n  legacy:\n    react: ^16.0.0\n    vue: ^2.7.0\n    vue: ^2.7.16\n",
+        )
+        .unwrap();
+
+        let (_, catalogs) = parse_workspace_file(&path).unwrap();
+        assert_eq!(catalogs.default["react"], "^18.3.0");
+        assert_eq!(
+            catalogs.duplicate_keys(),
+            vec![
+                CatalogEntry {
+                    catalog_name: None,
+                    dependency_name: "react".to_string(),
+                },
+                CatalogEntry {
+                    catalog_name: Some("legacy".to_string()),
+                    dependency_name: "vue".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn catalog_entries_may_list_several_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(
+            &path,
+            "catalog:\n  react: [^18.3.1, ^18.2.0]\n  lodash: ^4.17.21\n\
+             catalogs:\n  legacy:\n    react:\n      - ^16.14.0\n      - ^16.8.0\n",
+        )
+        .unwrap();
+
+        let (_, catalogs) = parse_workspace_file(&path).unwrap();
+        let entry = |catalog: Option<&str>, dep: &str| CatalogEntry {
+            catalog_name: catalog.map(str::to_string),
+            dependency_name: dep.to_string(),
+        };
+        assert_eq!(catalogs.default["react"], "^18.3.1");
+        assert_eq!(
+            catalogs.accepted_versions(&entry(None, "react")),
+            ["^18.3.1", "^18.2.0"]
+        );
+        assert_eq!(
+            catalogs.accepted_versions(&entry(None, "lodash")),
+            ["^4.17.21"]
+        );
+        assert_eq!(
+            catalogs
+                .display_version(&entry(Some("legacy"), "react"))
+                .unwrap(),
+            "^16.14.0, ^16.8.0"
+        );
+        assert_eq!(catalogs.entry_lines()[&entry(Some("legacy"), "react")], 6);
+    }
+
+    #[test]
+    fn empty_list_of_versions_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(&path, "catalog:\n  react: []\n").unwrap();
+
+        let err = parse_workspace_file(&path).unwrap_err();
+        let source = std::error::Error::source(&err).unwrap().to_string();
+        assert!(source.contains("'react' has an empty list of versions"));
+    }
+
+    #[test]
+    fn entry_lines_point_at_each_catalog_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnpm-workspace.yaml");
+        std::fs::write(
+            &path,
+            "packages:\n  - apps/*\n\
+             catalog:\n  # UI\n  react: ^18.2.0\n  \"@types/react\": ^18.0.0\n\
+             catalogs:\n  legacy:\n    react: ^16.0.0\n\n  next:\n    react: ^19.0.0\n    react: ^19.1.0\n",
+        )
+        .unwrap();
+
+        let (_, catalogs) = parse_workspace_file(&path).unwrap();
+        let line = |catalog: Option<&str>, dep: &str| {
+            catalogs.entry_lines()[&CatalogEntry {
+                catalog_name: catalog.map(str::to_string),
+                dependency_name: dep.to_string(),
+            }]
+        };
+        assert_eq!(line(None, "react"), 5);
+        assert_eq!(line(None, "@types/react"), 6);
+        assert_eq!(line(Some("legacy"), "react"), 9);
+        // The entry that takes effect
+        assert_eq!(line(Some("next"), "react"), 13);
+        assert_eq!(catalogs.entry_lines().len(), 4);
+    }
+
+    #[test]
+    fn remove_entry_from_catalogs_default() {
+        let yaml = "catalogs:\n  default:\n    react: \"^18.2.0\"\n    jquery: \"^3.6.0\"\n";
+        let (dir, yaml_path) = write_temp_yaml(yaml);
+
+        let entries = vec![CatalogEntry {
+            catalog_name: None,
+            dependency_name: "jquery".to_string(),
+        }];
+
+        assert_eq!(remove_entries(dir.path(), &entries), 1);
+        let result = std::fs::read_to_string(&yaml_path).unwrap();
+        assert!(result.contains("react: \"^18.2.0\""));
+        assert!(!result.contains("jquery"));
+    }
+
+    #[test]
+    fn remove_entry_written_as_a_block_list() {
+        let yaml = "catalog:\n  react:\n    - ^18.3.1\n    - ^18.2.0\n  vue:\n  - ^3.4.0\n  lodash: ^4.17.21\n";
+        let (dir, yaml_path) = write_temp_yaml(yaml);
+
+        let entries = vec![
+            CatalogEntry {
+                catalog_name: None,
+                dependency_name: "react".to_string(),
+            },
+            CatalogEntry {
+                catalog_name: None,
+                dependency_name: "vue".to_string(),
+            },
+        ];
+
+        assert_eq!(remove_entries(dir.path(), &entries), 2);
+        let result = std::fs::read_to_string(&yaml_path).unwrap();
+        assert_eq!(result, "catalog:\n  lodash: ^4.17.21\n");
+    }
+
+    #[test]
+    fn parse_workspace_file_reads_any_file_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("catalog.yaml");
+        std::fs::write(&path, "catalog:\n  react: \"^18.2.0\"\n").unwrap();
+
+        let (workspace, catalogs) = parse_workspace_file(&path).unwrap();
+        assert!(workspace.packages.is_empty());
+        assert!(catalogs.has_default_entry("react"));
+
+        assert!(matches!(
+            parse_workspace_file(&dir.path().join("missing.yaml")),
+            Err(LintError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn load_catalog_source_from_json_and_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("versions.json");
+        std::fs::write(&json, r#"{ "react": "^18.2.0", "lodash": "^4.17.21" }"#).unwrap();
+        let yaml = dir.path().join("versions.yml");
+        std::fs::write(&yaml, "react: \"^18.2.0\"\nlodash: ^4.17.21\n").unwrap();
+
+        for path in [&json, &yaml] {
+            let default = load_catalog_source(path).unwrap();
+            assert_eq!(
+                default.into_iter().collect::<Vec<_>>(),
+                vec![
+                    ("react".to_string(), "^18.2.0".to_string()),
+                    ("lodash".to_string(), "^4.17.21".to_string()),
+                ]
+            );
+        }
+
+        std::fs::write(&json, r#"{ "react": { "version": "^18.2.0" } }"#).unwrap();
+        let err = load_catalog_source(&json).unwrap_err();
+        assert!(format!("{err:#}").starts_with("Failed to parse"));
+    }
+
+    #[test]
+    fn relative_to_climbs_out_of_base() {
+        let rel = |path: &str, base: &str| relative_to(Path::new(path), Path::new(base));
+        assert_eq!(
+            rel("/repo/packages/app", "/repo"),
+            PathBuf::from("packages/app")
+        );
+        assert_eq!(
+            rel("/repo/packages/app", "/repo/tools"),
+            PathBuf::from("../packages/app")
+        );
+        assert_eq!(rel("/repo", "/repo/packages/app"), PathBuf::from("../.."));
+        assert_eq!(rel("/repo", "/repo"), PathBuf::new());
+    }
+
     #[test]
     fn parse_default_catalog() {
         let yaml = r#"
@@ -530,15 +1222,24 @@ catalog:
   lodash: "^4.17.21"
 "#;
         let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
-        let catalogs = WorkspaceCatalogs {
-            default: ws.catalog,
-            named: ws.catalogs,
-        };
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
         assert!(catalogs.has_default_entry("react"));
         assert!(catalogs.has_default_entry("lodash"));
         assert!(!catalogs.has_default_entry("express"));
     }
 
+    #[test]
+    fn parse_named_catalog_without_entries() {
+        let yaml = r#"
+catalogs:
+  react16:
+  react17: {}
+"#;
+        let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
+        assert!(ws.catalogs["react16"].is_empty());
+        assert!(ws.catalogs["react17"].is_empty());
+    }
+
     #[test]
     fn parse_named_catalogs() {
         let yaml = r#"
@@ -550,10 +1251,7 @@ catalogs:
     react: "^17.0.2"
 "#;
         let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
-        let catalogs = WorkspaceCatalogs {
-            default: ws.catalog,
-            named: ws.catalogs,
-        };
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
         assert!(catalogs.has_catalog("react16"));
         assert!(catalogs.has_named_entry("react16", "react"));
         assert!(!catalogs.has_named_entry("react16", "express"));
@@ -570,16 +1268,40 @@ catalogs:
     react: "^16.0.0"
 "#;
         let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
-        let catalogs = WorkspaceCatalogs {
-            default: ws.catalog,
-            named: ws.catalogs,
-        };
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
         let found = catalogs.find_dependency("react");
         assert_eq!(found.len(), 2);
         assert_eq!(found[0], None); // default
         assert_eq!(found[1], Some("legacy".to_string()));
     }
 
+    #[test]
+    fn find_dependency_with_scoped_and_dotted_names() {
+        let yaml = r#"
+catalog:
+  "@types/node": "^20.11.0"
+  "@babel/core": "^7.24.0"
+catalogs:
+  "@acme/tooling":
+    "@babel/core": "^7.22.0"
+    lodash.merge: "^4.6.2"
+"#;
+        let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
+        assert_eq!(catalogs.find_dependency("@types/node"), vec![None]);
+        assert_eq!(
+            catalogs.find_dependency("@babel/core"),
+            vec![None, Some("@acme/tooling".to_string())]
+        );
+        assert_eq!(
+            catalogs.find_dependency("lodash.merge"),
+            vec![Some("@acme/tooling".to_string())]
+        );
+        // Only exact names match; the bare name of a scoped package is a different package
+        assert!(catalogs.find_dependency("node").is_empty());
+        assert!(catalogs.find_dependency("@babel").is_empty());
+    }
+
     #[test]
     fn all_entries() {
         let yaml = r#"
@@ -590,10 +1312,7 @@ catalogs:
     jquery: "^3.6.0"
 "#;
         let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
-        let catalogs = WorkspaceCatalogs {
-            default: ws.catalog,
-            named: ws.catalogs,
-        };
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
         let entries = catalogs.all_entries();
         assert_eq!(entries.len(), 2);
         assert!(entries.contains(&CatalogEntry {
@@ -613,10 +1332,7 @@ packages:
   - "packages/*"
 "#;
         let ws: PnpmWorkspaceYaml = serde_yaml::from_str(yaml).unwrap();
-        let catalogs = WorkspaceCatalogs {
-            default: ws.catalog,
-            named: ws.catalogs,
-        };
+        let catalogs = WorkspaceCatalogs::new(ws.catalog, ws.catalogs);
         assert!(!catalogs.has_default_entry("anything"));
         assert!(catalogs.all_entries().is_empty());
         assert!(catalogs.find_dependency("react").is_empty());
@@ -667,6 +1383,29 @@ packages:
         (dir, yaml_path)
     }
 
+    #[test]
+    fn remove_scoped_entries_from_scoped_named_catalog() {
+        let yaml = "catalog:\n  \"@types/node\": \"^20.11.0\"\n  lodash.merge: \"^4.6.2\"\n\ncatalogs:\n  \"@acme/design\":\n    \"@acme/ui\": \"^2.0.0\"\n    '@acme/icons': \"^1.0.0\"\n";
+        let (dir, yaml_path) = write_temp_yaml(yaml);
+
+        let entries = vec![
+            CatalogEntry {
+                catalog_name: None,
+                dependency_name: "lodash.merge".to_string(),
+            },
+            CatalogEntry {
+                catalog_name: Some("@acme/design".to_string()),
+                dependency_name: "@acme/icons".to_string(),
+            },
+        ];
+
+        assert_eq!(remove_entries(dir.path(), &entries), 2);
+        assert_eq!(
+            std::fs::read_to_string(&yaml_path).unwrap(),
+            "catalog:\n  \"@types/node\": \"^20.11.0\"\n\ncatalogs:\n  \"@acme/design\":\n    \"@acme/ui\": \"^2.0.0\"\n"
+        );
+    }
+
     #[test]
     fn remove_default_catalog_entry() {
         let yaml = "packages:\n  - \"packages/*\"\n\ncatalog:\n  react: \"^18.2.0\"\n  lodash: \"^4.17.21\"\n";
@@ -677,7 +1416,7 @@ packages:
             dependency_name: "lodash".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -696,7 +1435,7 @@ packages:
             dependency_name: "lodash".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -714,7 +1453,7 @@ packages:
             dependency_name: "jquery".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -734,7 +1473,7 @@ packages:
             dependency_name: "jquery".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -753,7 +1492,7 @@ packages:
             dependency_name: "@types/react".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -771,7 +1510,7 @@ packages:
             dependency_name: "lodash".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 0);
     }
 
@@ -785,7 +1524,7 @@ packages:
             dependency_name: "leftpad".to_string(),
         }];
 
-        let count = remove_catalog_entries(dir.path(), &entries).unwrap();
+        let count = remove_entries(dir.path(), &entries);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -806,7 +1545,7 @@ packages:
             version: "^4.18.0".to_string(),
         }];
 
-        let count = add_catalog_entries(dir.path(), &additions).unwrap();
+        let count = add_entries(dir.path(), &additions);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -827,7 +1566,7 @@ packages:
             version: "^18.0.0".to_string(),
         }];
 
-        let count = add_catalog_entries(dir.path(), &additions).unwrap();
+        let count = add_entries(dir.path(), &additions);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -844,7 +1583,7 @@ packages:
             version: "^18.2.0".to_string(),
         }];
 
-        let count = add_catalog_entries(dir.path(), &additions).unwrap();
+        let count = add_entries(dir.path(), &additions);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -868,7 +1607,7 @@ packages:
             },
         ];
 
-        let count = add_catalog_entries(dir.path(), &additions).unwrap();
+        let count = add_entries(dir.path(), &additions);
         assert_eq!(count, 2);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();
@@ -881,6 +1620,56 @@ packages:
         assert!(react_pos < zod_pos);
     }
 
+    #[test]
+    fn add_entry_lands_in_order_after_other_sections() {
+        let yaml = "catalogs:\n  legacy:\n    zod: \"^2.0.0\"\noverrides:\n  axios: \"1.6.0\"\n\ncatalog:\n  \"@types/node\": \"^20.11.0\"\n  express: \"^4.18.0\"\n  react: \"^18.2.0\"\n";
+        let (dir, yaml_path) = write_temp_yaml(yaml);
+
+        let additions = vec![
+            crate::collect::CatalogAddition {
+                dependency_name: "lodash".to_string(),
+                version: "^4.17.21".to_string(),
+            },
+            crate::collect::CatalogAddition {
+                dependency_name: "@babel/core".to_string(),
+                version: "^7.24.0".to_string(),
+            },
+        ];
+
+        assert_eq!(add_entries(dir.path(), &additions), 2);
+        let result = std::fs::read_to_string(&yaml_path).unwrap();
+        assert!(result.ends_with(
+            "catalog:\n  \"@babel/core\": ^7.24.0\n  \"@types/node\": \"^20.11.0\"\n  express: \"^4.18.0\"\n  lodash: ^4.17.21\n  react: \"^18.2.0\"\n"
+        ));
+    }
+
+    #[test]
+    fn add_entry_goes_under_catalogs_default_without_a_catalog_section() {
+        let yaml = "packages:\n  - \"packages/*\"\ncatalogs:\n  default:\n    react: \"^18.2.0\"\n  legacy:\n    react: \"^16.14.0\"\n";
+        let (dir, yaml_path) = write_temp_yaml(yaml);
+
+        let additions = vec![
+            crate::collect::CatalogAddition {
+                dependency_name: "lodash".to_string(),
+                version: "^4.17.21".to_string(),
+            },
+            crate::collect::CatalogAddition {
+                dependency_name: "zod".to_string(),
+                version: "^3.0.0".to_string(),
+            },
+        ];
+
+        assert_eq!(add_entries(dir.path(), &additions), 2);
+        let result = std::fs::read_to_string(&yaml_path).unwrap();
+        assert_eq!(
+            result,
+            "packages:\n  - \"packages/*\"\ncatalogs:\n  default:\n    lodash: ^4.17.21\n    react: \"^18.2.0\"\n    zod: ^3.0.0\n  legacy:\n    react: \"^16.14.0\"\n"
+        );
+        // Still one default catalog, as pnpm requires
+        let (_, catalogs) = parse_workspace_str(&result, &yaml_path).unwrap();
+        assert_eq!(catalogs.default.len(), 3);
+    }
+
     #[test]
     fn add_entry_appends_when_unsorted() {
         let yaml = "catalog:\n  react: \"^18.2.0\"\n  axios: \"^1.0.0\"\n";
@@ -891,7 +1680,7 @@ packages:
             version: "^4.18.0".to_string(),
         }];
 
-        let count = add_catalog_entries(dir.path(), &additions).unwrap();
+        let count = add_entries(dir.path(), &additions);
         assert_eq!(count, 1);
 
         let result = std::fs::read_to_string(&yaml_path).unwrap();